@@ -1,6 +1,8 @@
+use serde::Serialize;
+use serde::Deserialize;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Angle {
     pub xy: f32,
     pub xz: f32,
@@ -32,6 +34,80 @@ impl Angle {
     }
 }
 
+/// One of the six rotation planes of 4D space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Plane {
+    XY,
+    XZ,
+    XW,
+    YZ,
+    YW,
+    ZW,
+}
+
+impl Plane {
+    pub const ALL: [Plane; 6] = [Plane::XY, Plane::XZ, Plane::XW, Plane::YZ, Plane::YW, Plane::ZW];
+
+    /// Indices of the two axes spanning the plane, X = 0 ... W = 3.
+    pub fn axes(self) -> (usize, usize) {
+        match self {
+            Plane::XY => (0, 1),
+            Plane::XZ => (0, 2),
+            Plane::XW => (0, 3),
+            Plane::YZ => (1, 2),
+            Plane::YW => (1, 3),
+            Plane::ZW => (2, 3),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Plane::XY => "XY",
+            Plane::XZ => "XZ",
+            Plane::XW => "XW",
+            Plane::YZ => "YZ",
+            Plane::YW => "YW",
+            Plane::ZW => "ZW",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Plane> {
+        match name.to_lowercase().as_str() {
+            "xy" => Some(Plane::XY),
+            "xz" => Some(Plane::XZ),
+            "xw" => Some(Plane::XW),
+            "yz" => Some(Plane::YZ),
+            "yw" => Some(Plane::YW),
+            "zw" => Some(Plane::ZW),
+            _ => None,
+        }
+    }
+}
+
+impl Angle {
+    pub fn get(&self, plane: Plane) -> f32 {
+        match plane {
+            Plane::XY => self.xy,
+            Plane::XZ => self.xz,
+            Plane::XW => self.xw,
+            Plane::YZ => self.yz,
+            Plane::YW => self.yw,
+            Plane::ZW => self.zw,
+        }
+    }
+
+    pub fn set(&mut self, plane: Plane, value: f32) {
+        match plane {
+            Plane::XY => self.xy = value,
+            Plane::XZ => self.xz = value,
+            Plane::XW => self.xw = value,
+            Plane::YZ => self.yz = value,
+            Plane::YW => self.yw = value,
+            Plane::ZW => self.zw = value,
+        }
+    }
+}
+
 impl Display for Angle {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "xy: {}\nxz: {}\nxw: {}\nyz: {}\nyw: {}\nzw: {}", self.xy, self.xz, self.xw, self.yz, self.yw, self.zw)
@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use crate::angle::Angle;
+use crate::angle::Plane;
+use crate::rotor::Rotor;
+use crate::error::Error;
+use crate::render::render;
+use crate::objects::Object;
+use crate::objects::Camera;
+
+/// Rotation of one plane from `from` to `to` (radians) over the animation.
+#[derive(Debug, Copy, Clone)]
+pub struct Sweep {
+    pub plane: Plane,
+    pub from:  f32,
+    pub to:    f32,
+}
+
+impl Sweep {
+    /// Parses `plane:from:to`, e.g. `xw:0:6.2832`.
+    pub fn parse(arg: &str) -> Option<Sweep> {
+        let parts: Vec<&str> = arg.split(':').collect();
+        if parts.len() != 3 { return None; }
+        Some(Sweep {
+            plane: Plane::parse(parts[0])?,
+            from:  parts[1].parse().ok()?,
+            to:    parts[2].parse().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub base:   Angle, // planes without a sweep keep these values
+    pub sweeps: Vec<Sweep>,
+    pub frames: u32,
+}
+
+impl Animation {
+    /// The end of every range is excluded, so a full turn loops without a repeated frame.
+    pub fn angle_at(&self, frame: u32) -> Angle {
+        let t = frame as f32 / self.frames.max(1) as f32;
+        let mut angle = self.base;
+        for sweep in &self.sweeps {
+            angle.set(sweep.plane, sweep.from + (sweep.to - sweep.from) * t);
+        }
+        angle
+    }
+}
+
+fn gif_error(e: gif::EncodingError) -> Error {
+    match e {
+        gif::EncodingError::Io(e) => Error::Io(e),
+        e => Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)),
+    }
+}
+
+/// Writes a looping GIF, `delay` is the frame duration in hundredths of a second.
+pub fn write_gif<W: Write>(
+    writer:    W,
+    objects:   &Vec<Object>,
+    animation: &Animation,
+    camera:    &Camera,
+    size:      (u16, u16),
+    delay:     u16,
+) -> Result<(), Error> {
+    let mut encoder = gif::Encoder::new(writer, size.0, size.1, &[]).map_err(gif_error)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+    for i in 0..animation.frames {
+        let rotor = Rotor::from(animation.angle_at(i));
+        let mut image = render(objects, &rotor, camera, (size.0 as u32, size.1 as u32), true);
+        let mut frame = gif::Frame::from_rgba_speed(size.0, size.1, &mut image.pixels, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(gif_error)?;
+    }
+    Ok(())
+}
+
+pub fn save_gif<P: AsRef<Path>>(
+    path:      P,
+    objects:   &Vec<Object>,
+    animation: &Animation,
+    camera:    &Camera,
+    size:      (u16, u16),
+    delay:     u16,
+) -> Result<(), Error> {
+    let file = File::create(path).map_err(Error::Io)?;
+    write_gif(std::io::BufWriter::new(file), objects, animation, camera, size, delay)
+}
+
+/// Writes `<prefix>_0000.png`, `<prefix>_0001.png`, ... and returns their paths.
+pub fn save_png_sequence(
+    prefix:    &str,
+    objects:   &Vec<Object>,
+    animation: &Animation,
+    camera:    &Camera,
+    size:      (u32, u32),
+) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+    for i in 0..animation.frames {
+        let path = PathBuf::from(format!("{}_{:04}.png", prefix, i));
+        render(objects, &Rotor::from(animation.angle_at(i)), camera, size, true).save_png(&path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
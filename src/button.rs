@@ -1,5 +1,8 @@
 use super::*;
 
+const MENU_ITEM_W: f32 = 220.0;
+const MENU_ITEM_H: f32 = 20.0;
+
 #[derive(Debug, Copy, Clone)]
 pub enum Align {
     Middle,
@@ -42,10 +45,24 @@ impl CheckButton {
 pub enum ButtonType {
     CreateTesseract,
     CreateSphere3D,
+    CreateCell5,
+    CreateCell16,
+    CreateCell24,
+    CreateCell120,
+    CreateCell600,
+    ImportObj,
     SelectionType,
     Settings,
     Import,
     Export,
+    ExportSvg,
+    ObjMenu,
+    Obj(ObjMenuItem),
+    Projection,
+    ViewMenu,
+    View(ViewPreset),
+    TurntableMenu,
+    Turntable(TurntableItem),
     Close,
     Info,
 }
@@ -185,4 +202,23 @@ impl Button {
             _ => false,
         }
     }
-}
\ No newline at end of file
+
+    /// Opens a drop-down menu of text items under the top left row button at `x`,
+    /// or closes it if it is open. Any other open menu is closed.
+    pub fn toggle_menu(buttons: &mut Vec<Button>, x: f32, items: Vec<ButtonType>) {
+        let open = buttons.iter().any(|b| b.offset() == (x, MENU_ITEM_H));
+        buttons.retain(|b| b.offset().1 == 0.0);
+        if open { return; }
+        for (i, item) in items.into_iter().enumerate() {
+            buttons.push(Button::Click(ClickButton::new(
+                x,
+                MENU_ITEM_H * (i + 1) as f32,
+                MENU_ITEM_W,
+                MENU_ITEM_H,
+                None,
+                Align::TopLeft,
+                item,
+            )));
+        }
+    }
+}
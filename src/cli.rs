@@ -0,0 +1,207 @@
+use std::str::FromStr;
+use std::convert::TryFrom;
+use crate::animation::*;
+use crate::import::load_scene_from_path;
+use crate::rotor::Rotor;
+use crate::render::render_to_png;
+use crate::objects::Camera;
+use crate::objects::CAMERA_DISTANCE;
+
+const USAGE: &str = "usage: polytope_4d render <scene.4dp | scene.json | scene.off> <image.png> [width height] [--distance d]";
+const ANIMATE_USAGE: &str = "usage: polytope_4d animate <scene.4dp | scene.json | scene.off> <movie.gif | frame_prefix> <frames> <plane:from:to>... [--size width height] [--delay hundredths] [--distance d]";
+const DEFAULT_DELAY: u16 = 4; // GIF frame duration, hundredths of a second
+
+fn parse_size(args: &[String]) -> Option<(u32, u32)> {
+    match args {
+        [] => Some((800, 600)),
+        [w, h] => Some((w.parse().ok()?, h.parse().ok()?)).filter(|(w, h)| *w > 0 && *h > 0),
+        _ => None,
+    }
+}
+
+/// Removes `name value` from `args` and parses the value, `Err` if it is
+/// missing or invalid, `Ok(None)` if there is no such option.
+fn take_value<T: FromStr>(args: &mut Vec<String>, name: &str) -> Result<Option<T>, ()> {
+    let i = match args.iter().position(|a| a == name) {
+        Some(i) => i,
+        None => return Ok(None),
+    };
+    let value = args.get(i + 1).and_then(|v| v.parse().ok()).ok_or(())?;
+    args.drain(i..i + 2);
+    Ok(Some(value))
+}
+
+/// Removes `--distance d` from `args` and returns the camera on the W axis
+/// that far from the origin, `CAMERA_DISTANCE` away without the option.
+/// `None` if the distance is missing or not positive.
+fn take_camera(args: &mut Vec<String>) -> Option<Camera> {
+    let d = take_value(args, "--distance").ok()?.unwrap_or(CAMERA_DISTANCE);
+    if !(d.is_finite() && d > 0.0) { return None; }
+    Some(Camera::on_w(d))
+}
+
+fn render_command(args: &[String]) -> i32 {
+    let mut args = args.to_vec();
+    let camera = match take_camera(&mut args) {
+        Some(camera) => camera,
+        None => { eprintln!("{}", USAGE); return 2; },
+    };
+    if args.len() < 2 {
+        eprintln!("{}", USAGE);
+        return 2;
+    }
+    let size = match parse_size(&args[2..]) {
+        Some(size) => size,
+        None => { eprintln!("{}", USAGE); return 2; },
+    };
+    let (objects, angle) = match load_scene_from_path(&args[0]) {
+        Ok(scene) => scene,
+        Err(e) => { eprintln!("{}", e); return 1; },
+    };
+    match render_to_png(&args[1], &objects, &Rotor::from(angle), &camera, size) {
+        Ok(_) => 0,
+        Err(e) => { eprintln!("{}", e); 1 },
+    }
+}
+
+/// Writes a GIF if the output ends with `.gif`, otherwise a numbered PNG sequence.
+fn animate_command(args: &[String]) -> i32 {
+    let mut args = args.to_vec();
+    let camera = match take_camera(&mut args) {
+        Some(camera) => camera,
+        None => { eprintln!("{}", ANIMATE_USAGE); return 2; },
+    };
+    let delay = match take_value(&mut args, "--delay") {
+        Ok(delay) => delay.unwrap_or(DEFAULT_DELAY),
+        Err(_) => { eprintln!("{}", ANIMATE_USAGE); return 2; },
+    };
+    let mut size = (800, 600);
+    if let Some(i) = args.iter().position(|a| a == "--size") {
+        size = match parse_size(args.get(i + 1..i + 3).unwrap_or(&[])) {
+            Some(size) => size,
+            None => { eprintln!("{}", ANIMATE_USAGE); return 2; },
+        };
+        args.drain(i..i + 3);
+    }
+    if args.len() < 4 {
+        eprintln!("{}", ANIMATE_USAGE);
+        return 2;
+    }
+    let frames = match args[2].parse() {
+        Ok(f) => f,
+        Err(_) => { eprintln!("{}", ANIMATE_USAGE); return 2; },
+    };
+    let mut sweeps = vec![];
+    for arg in &args[3..] {
+        match Sweep::parse(arg) {
+            Some(sweep) => sweeps.push(sweep),
+            None => { eprintln!("invalid sweep '{}'\n{}", arg, ANIMATE_USAGE); return 2; },
+        }
+    }
+    let gif = args[1].ends_with(".gif");
+    let gif_size = match (u16::try_from(size.0), u16::try_from(size.1)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ if gif => {
+            eprintln!("GIF frames are at most {} pixels wide and high\n{}", u16::MAX, ANIMATE_USAGE);
+            return 2;
+        },
+        _ => (0, 0),
+    };
+    let (objects, angle) = match load_scene_from_path(&args[0]) {
+        Ok(scene) => scene,
+        Err(e) => { eprintln!("{}", e); return 1; },
+    };
+    let animation = Animation { base: angle, sweeps, frames };
+    let res = if gif {
+        save_gif(&args[1], &objects, &animation, &camera, gif_size, delay)
+    } else {
+        save_png_sequence(&args[1], &objects, &animation, &camera, size).map(|_| ())
+    };
+    match res {
+        Ok(_) => 0,
+        Err(e) => { eprintln!("{}", e); 1 },
+    }
+}
+
+/// Runs a headless command if one is given on the command line
+/// and returns its exit code, `None` means the editor should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.get(1).map(|s| s.as_str()) {
+        Some("render") => Some(render_command(&args[2..])),
+        Some("animate") => Some(animate_command(&args[2..])),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::Object;
+
+    fn run_args(args: &[&str]) -> Option<i32> {
+        run(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn options() {
+        let mut args = vec!["a".to_string(), "--distance".to_string(), "3".to_string(), "b".to_string()];
+        let distance = |args: &mut Vec<String>| take_camera(args).map(|c| c.distance());
+        assert_eq!(distance(&mut args), Some(3.0));
+        assert_eq!(args, ["a", "b"]);
+        assert_eq!(distance(&mut args), Some(CAMERA_DISTANCE));
+        for bad in [&["--distance"][..], &["--distance", "x"], &["--distance", "-1"], &["--distance", "inf"]] {
+            let mut args = bad.iter().map(|s| s.to_string()).collect();
+            assert!(take_camera(&mut args).is_none(), "{:?}", bad);
+        }
+        assert_eq!(run_args(&["p", "render", "a.4dp", "b.png", "--distance", "0"]), Some(2));
+        assert_eq!(run_args(&["p", "render", "/nonexistent/a.4dp", "b.png", "--distance", "3"]), Some(1));
+        assert_eq!(run_args(&["p"]), None);
+    }
+
+    #[test]
+    fn animate_options() {
+        let animate = |options: &[&str]| {
+            let mut args = vec!["p", "animate", "/nonexistent/a.4dp", "b.gif", "4", "xw:0:1"];
+            args.extend_from_slice(options);
+            run_args(&args)
+        };
+        assert_eq!(animate(&["--distance", "3", "--delay", "7"]), Some(1));
+        assert_eq!(animate(&["--distance", "x"]), Some(2));
+        assert_eq!(animate(&["--delay", "-3"]), Some(2));
+        assert_eq!(animate(&["--delay", "70000"]), Some(2));
+        assert_eq!(animate(&["--size", "0", "10"]), Some(2));
+        assert_eq!(animate(&["--size", "70000", "10"]), Some(2));
+        let png = ["p", "animate", "/nonexistent/a.4dp", "b", "4", "xw:0:1", "--size", "70000", "10"];
+        assert_eq!(run_args(&png), Some(1));
+        assert_eq!(run_args(&["p", "render", "/nonexistent/a.4dp", "b.png", "0", "10"]), Some(2));
+    }
+
+    #[test]
+    fn delay() {
+        let dir = std::env::temp_dir().join("polytope_4d_cli_delay");
+        assert!(std::fs::create_dir_all(&dir).is_ok());
+        let scene = dir.join("tesseract.4dp");
+        assert!(Object::tesseract().save_to_path(&scene).is_ok());
+        let gif = dir.join("tesseract.gif");
+        let args = ["p", "animate", scene.to_str().unwrap(), gif.to_str().unwrap(), "2", "xw:0:1", "--size", "32", "24", "--delay", "7"];
+        assert_eq!(run_args(&args), Some(0));
+        // graphic control extension: 0x21 0xF9, block size 4, flags, delay (u16, little endian)
+        let bytes = std::fs::read(&gif).unwrap();
+        assert!(bytes.windows(6).any(|w| w[..3] == [0x21, 0xF9, 4] && w[4..] == [7, 0]));
+    }
+
+    #[test]
+    fn distance() {
+        let dir = std::env::temp_dir().join("polytope_4d_cli_distance");
+        assert!(std::fs::create_dir_all(&dir).is_ok());
+        let scene = dir.join("tesseract.4dp");
+        assert!(Object::tesseract().save_to_path(&scene).is_ok());
+        let image = |name: &str, distance: &str| {
+            let path = dir.join(name);
+            let args = ["p", "render", scene.to_str().unwrap(), path.to_str().unwrap(), "64", "48", "--distance", distance];
+            assert_eq!(run_args(&args), Some(0));
+            std::fs::read(path).unwrap()
+        };
+        assert_ne!(image("near.png", "3"), image("far.png", "20"));
+    }
+}
@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+use macroquad::prelude::show_mouse;
+
+/// Shows a native file dialog with the mouse cursor visible and returns the
+/// chosen path, e.g. `show_dialog(|| rfd::FileDialog::new().pick_file())`.
+pub fn show_dialog<F: FnOnce() -> Option<PathBuf>>(dialog: F) -> Option<String> {
+    show_mouse(true);
+    let path = dialog();
+    show_mouse(false);
+    path.and_then(|p| p.to_str().map(|s| s.to_string()))
+}
@@ -1,5 +1,14 @@
 use super::*;
 
+pub const BACKGROUND_COLOR:        Color = Color::new(0.55294, 0.55294, 0.55294, 1.0);
+pub const EDGE_COLOR:              Color = Color::new(0.1, 0.1, 0.1, 1.0);
+pub const SELECTED_EDGE_OUTLINE:   Color = Color::new(0.1, 0.2, 0.4, 1.0);
+pub const SELECTED_EDGE_COLOR:     Color = Color::new(0.1, 0.6, 1.0, 1.0);
+pub const VERTEX_COLOR:            Color = Color::new(0.1, 0.1, 0.1, 1.0);
+pub const SELECTED_VERTEX_OUTLINE: Color = Color::new(0.0, 0.2, 0.4, 1.0);
+pub const SELECTED_VERTEX_COLOR:   Color = Color::new(0.0, 0.6, 1.0, 1.0);
+pub const AXE_LABEL_COLOR:         Color = Color::new(0.3, 0.3, 0.3, 1.0);
+
 pub fn draw_cursor(cursor: &Cursor) {
     // println!("{}", cursor.rect);
     if cursor.rect {
@@ -63,20 +72,23 @@ pub fn draw_motion_axes(axes: &MotionAxes) {
     }
 }
 
-fn draw_axe(off: (f32, f32), xy: (f32, f32), name: &str) {
-    let color = match name {
+pub fn axe_color(name: &str) -> Color {
+    match name {
         "X" => Color::new(1.0, 0.0, 0.0, 1.0),
         "Y" => Color::new(0.0, 1.0, 0.0, 1.0),
         "Z" => Color::new(0.0, 0.0, 1.0, 1.0),
          _  => Color::new(1.0, 0.0, 1.0, 1.0),
-    };
-    draw_line(off.0, off.1, xy.0 + off.0, xy.1 + off.1, 2.0, color);
+    }
+}
+
+fn draw_axe(off: (f32, f32), xy: (f32, f32), name: &str) {
+    draw_line(off.0, off.1, xy.0 + off.0, xy.1 + off.1, 2.0, axe_color(name));
     draw_text_ex(name, xy.0 + off.0 + 10.0, xy.1 + off.1, TextParams {
         font: Font::default(),
         font_size: 18,
         font_scale: 1.0,
         font_scale_aspect: 1.0,
-        color: Color::new(0.3, 0.3, 0.3, 1.0),
+        color: AXE_LABEL_COLOR,
     })
 }
 
@@ -104,6 +116,8 @@ pub fn draw_main_window(
     motion_axes: &MotionAxes,
     cursor:      &Cursor,
     cursor_drawn: &mut bool,
+    slice:       Option<(&SliceView, f32)>,
+    camera:      &Camera,
 ) {
     if window.is_hidden() { return }
     for obj in objects.iter() {
@@ -113,7 +127,11 @@ pub fn draw_main_window(
         }
     }
     draw_axes(axes, window.config().w, window.config().h);
-    draw_motion_axes(motion_axes);
+    match slice {
+        Some((slice, offset)) => draw_slider(slice, offset, window),
+        None => draw_motion_axes(motion_axes),
+    }
+    draw_projection_label(camera, window);
     if !*cursor_drawn {
         draw_cursor(cursor);
         *cursor_drawn = true;
@@ -125,6 +143,38 @@ pub fn draw_main_window(
     }
 }
 
+/// Offset slider of the slice view with the hyperplane equation above the handle.
+pub fn draw_slider(slice: &SliceView, offset: f32, window: &Window) {
+    let (x, y, w) = slice.slider(window);
+    let handle = x + w * slice.position;
+    draw_line(x, y, x + w, y, 2.0, Color::new(0.3, 0.3, 0.3, 1.0));
+    draw_line(x, y, handle, y, 2.0, axe_color(AXIS_NAMES[slice.axis]));
+    draw_circle(handle, y, 6.0, SELECTED_VERTEX_OUTLINE);
+    draw_circle(handle, y, 5.0, SELECTED_VERTEX_COLOR);
+    let label = format!("{} = {:.2}", AXIS_NAMES[slice.axis], offset);
+    draw_text_ex(&label, handle - 20.0, y - 12.0, TextParams {
+        font: Font::default(),
+        font_size: 18,
+        font_scale: 1.0,
+        font_scale_aspect: 1.0,
+        color: AXE_LABEL_COLOR,
+    })
+}
+
+/// Current 4D→3D and 3D→2D projection modes in the bottom right corner.
+pub fn draw_projection_label(camera: &Camera, window: &Window) {
+    let (x, y, w, h) = window.as_tuple();
+    let label = format!("4D→3D: {}, 3D→2D: {}", camera.projection.name(), camera.projection3d.name());
+    let size = measure_text(&label, Some(*COMFORTAA), 16, 1.0);
+    draw_text_ex(&label, x + w - size.width - 10.0, y + h - 10.0, TextParams {
+        font: *COMFORTAA,
+        font_size: 16,
+        font_scale: 1.0,
+        font_scale_aspect: 1.0,
+        color: AXE_LABEL_COLOR,
+    })
+}
+
 pub fn draw_border(x: f32, y: f32, w: f32, h: f32) {
     let tl = (x - 1.0, y - 1.0); // top left
     let tr = (x + w + 1.0, y - 1.0); // top right
@@ -195,12 +245,16 @@ pub fn draw_overlapping_window(
 }
 
 pub fn draw_windows(
-    windows:     &WindowGroup,
-    objects:     &Vec<Object>,
-    buttons:     &Vec<Button>,
-    axes:        &Axes,
-    motion_axes: &MotionAxes,
-    cursor:      &Cursor,
+    windows:      &WindowGroup,
+    objects:      &Vec<Object>,
+    buttons:      &Vec<Button>,
+    axes:         &Axes,
+    motion_axes:  &MotionAxes,
+    cursor:       &Cursor,
+    slice:        Option<(&SliceView, f32)>,
+    camera:       &Camera,
+    obj_settings: &ObjSettings,
+    turntable:    &Turntable,
 ) {
     let mut cursor_drawn = false;
     draw_main_window(
@@ -210,6 +264,8 @@ pub fn draw_windows(
         motion_axes,
         cursor,
         &mut cursor_drawn,
+        slice,
+        camera,
     );
     draw_start_window(
         windows,
@@ -219,6 +275,20 @@ pub fn draw_windows(
     draw_overlapping_window(&windows.instructions, cursor, &mut cursor_drawn);
     for button in buttons {
         draw_button( button, None);
+        let label = match button.get_type() {
+            ButtonType::View(preset) => preset.name().to_string(),
+            ButtonType::Obj(item) => obj_settings.label(item),
+            ButtonType::Turntable(item) => turntable.label(item),
+            _ => continue,
+        };
+        let (x, y) = button.get_pos(None);
+        draw_text_ex(&label, x + 5.0, y + 15.0, TextParams {
+            font: *COMFORTAA,
+            font_size: 16,
+            font_scale: 1.0,
+            font_scale_aspect: 1.0,
+            color: if button.is_hover() { Color::new(1.0, 1.0, 1.0, 1.0) } else { AXE_LABEL_COLOR },
+        });
     }
 }
 
@@ -226,10 +296,10 @@ pub fn draw_vertices(vertices: Vec<Vec4f>) {
     for v in vertices.into_iter() {
         if let Some(proj) = v.get_proj() {
             if v.selected {
-                draw_circle(proj.0, proj.1, 3.0, Color::new(0.0, 0.2, 0.4, 1.0));
-                draw_circle(proj.0, proj.1, 2.0, Color::new(0.0, 0.6, 1.0, 1.0));
+                draw_circle(proj.0, proj.1, 3.0, SELECTED_VERTEX_OUTLINE);
+                draw_circle(proj.0, proj.1, 2.0, SELECTED_VERTEX_COLOR);
             } else {
-                draw_circle(proj.0, proj.1, 2.0, Color::new(0.1, 0.1, 0.1, 1.0));
+                draw_circle(proj.0, proj.1, 2.0, VERTEX_COLOR);
             }
         }
     }
@@ -241,10 +311,10 @@ pub fn draw_edges(obj: &Object) {
         let b = obj.vertices[e.b].get_proj().unwrap();
         // println!("a: ({}, {}), b: ({}, {})", a.0, a.1, b.0, b.1);
         if e.selected {
-            draw_line(a.0, a.1, b.0, b.1, 2.0, Color::new(0.1, 0.2, 0.4, 1.0));
-            draw_line(a.0, a.1, b.0, b.1, 1.0, Color::new(0.1, 0.6, 1.0, 1.0));
+            draw_line(a.0, a.1, b.0, b.1, 2.0, SELECTED_EDGE_OUTLINE);
+            draw_line(a.0, a.1, b.0, b.1, 1.0, SELECTED_EDGE_COLOR);
         } else {
-            draw_line(a.0, a.1, b.0, b.1, 1.0, Color::new(0.1, 0.1, 0.1, 1.0));
+            draw_line(a.0, a.1, b.0, b.1, 1.0, EDGE_COLOR);
         }
     }
 }
@@ -2,6 +2,7 @@ use std::fmt;
 
 pub enum Error {
     Import(ImportError),
+    Io(std::io::Error),
     Cancel,
     Unknown,
 }
@@ -10,6 +11,38 @@ pub enum ImportError {
     FileNotFound,
     InvalidExtention,
     FileCorrupted,
+    UnsupportedVersion(u16),
+    Syntax(String),
+    /// First invalid read of a binary file: where it started and what was read.
+    Malformed { offset: usize, element: Element, reason: &'static str },
+}
+
+/// Part of a binary scene file, used to locate errors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Element {
+    Header,
+    Angle,
+    Name,
+    Counts,
+    Vertex,
+    Edge,
+    Face,
+    Cell,
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Header => write!(f, "header"),
+            Self::Angle => write!(f, "angle"),
+            Self::Name => write!(f, "object name"),
+            Self::Counts => write!(f, "element counts"),
+            Self::Vertex => write!(f, "vertex"),
+            Self::Edge => write!(f, "edge"),
+            Self::Face => write!(f, "face"),
+            Self::Cell => write!(f, "cell"),
+        }
+    }
 }
 
 impl fmt::Display for ImportError {
@@ -18,6 +51,11 @@ impl fmt::Display for ImportError {
             Self::FileNotFound => write!(f, "file not found"),
             Self::InvalidExtention => write!(f, "invalid extention"),
             Self::FileCorrupted => write!(f, "file corrupted"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            Self::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            Self::Malformed { offset, element, reason } => {
+                write!(f, "{} at byte {}: {}", element, offset, reason)
+            },
         }
     }
 }
@@ -26,6 +64,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Import(err) => write!(f, "Import failed: {}", err),
+            Self::Io(err) => write!(f, "I/O error: {}", err),
             Self::Cancel => write!(f, "Canceled"),
             Self::Unknown => write!(f, "Unknown error"),
         }
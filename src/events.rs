@@ -1,24 +1,38 @@
 use super::*;
-use std::f32::consts::PI;
 use macroquad::prelude::KeyCode;
 use macroquad::prelude::MouseButton;
 use macroquad::prelude::is_key_down;
 use macroquad::prelude::is_key_pressed;
 use macroquad::prelude::is_mouse_button_down;
+use macroquad::prelude::is_mouse_button_pressed;
+use macroquad::prelude::is_mouse_button_released;
 
 pub fn catch_mouse_event(
-    ms:          &mut MouseState,
-    buttons:     &mut Vec<Button>,
-    objects:     &mut Vec<Object>,
-    xy_last:    (f32, f32),
-    motion_axes: &mut MotionAxes,
-    angle:       &mut Angle,
-    windows:     &mut WindowGroup,
+    ms:           &mut MouseState,
+    buttons:      &mut Vec<Button>,
+    objects:      &mut Vec<Object>,
+    xy_last:      (f32, f32),
+    motion_axes:  &mut MotionAxes,
+    rotor:        &mut Rotor,
+    axes:         &Axes,
+    windows:      &mut WindowGroup,
+    slice:        &mut SliceView,
+    slices:       &Vec<Object>,
+    camera:       &mut Camera,
+    obj_settings: &mut ObjSettings,
+    transition:   &mut Option<ViewTransition>,
+    turntable:    &mut Turntable,
 ) {
+    if slice.enabled && slider_event(ms, slice, &windows.main) { return; }
+    if motion_axes.follow {
+        return follow_event(ms.pos, xy_last, rotor, camera, motion_axes, objects, &windows.main);
+    }
+    camera_event(ms, xy_last, camera);
     if is_mouse_button_down(MouseButton::Left) {
         lmb_down_event(&mut ms.is_lmb_down, &mut ms.lmb_click_timer, buttons, windows);
     } else if ms.is_lmb_down { // lmb up event
-        if ms.lmb_click_timer.elapsed().as_millis() < CLICK_TIMEOUT { // lmb click event
+        let on_button = buttons.iter().any(|b| b.is_hover());
+        if ms.lmb_click_timer.elapsed().as_millis() < CLICK_TIMEOUT && !on_button { // lmb click event
             lmb_click_event(
                 objects,
                 ms.pos,
@@ -26,11 +40,12 @@ pub fn catch_mouse_event(
                 windows,
             );
         }
-		lmb_up_event(buttons, objects, windows);
+        let slices = if slice.enabled { Some(slices) } else { None };
+		lmb_up_event(buttons, objects, rotor, axes, windows, slices, camera, obj_settings, transition, turntable);
         ms.is_lmb_down = false;
     } else if is_mouse_button_down(MouseButton::Right) {
         rmb_down_event(&mut ms.is_rmb_down, &mut ms.rmb_click_timer, motion_axes);
-        drag_event(ms.pos, xy_last, angle, ms.scroll_delta, motion_axes, objects, &windows.main);
+        drag_event(ms.pos, xy_last, rotor, camera, ms.scroll_delta, motion_axes, objects, &windows.main);
     } else if ms.is_rmb_down {
         mouse_up_event(&mut ms.is_rmb_down, motion_axes, objects);
     }
@@ -39,48 +54,105 @@ pub fn catch_mouse_event(
     }
 }
 
+/// Drags the slice view slider, returns true while the mouse is busy with it.
+pub fn slider_event(ms: &MouseState, slice: &mut SliceView, window: &Window) -> bool {
+    if !is_mouse_button_down(MouseButton::Left) {
+        slice.grabbed = false;
+        return false;
+    }
+    if !slice.grabbed && !ms.is_lmb_down && slice.slider_hovered(ms.pos, window) {
+        slice.grabbed = true;
+    }
+    if slice.grabbed { slice.drag_to(ms.pos, window); }
+    slice.grabbed
+}
+
+/// Zooms the camera with the mouse wheel (unless RMB is held, then the wheel
+/// rotates ZW) and pans it with the middle mouse button.
+pub fn camera_event(ms: &MouseState, xy_last: (f32, f32), camera: &mut Camera) {
+    if ms.scroll_delta != 0.0 && !is_mouse_button_down(MouseButton::Right) {
+        camera.zoom(ZOOM_STEP.powf(-ms.scroll_delta.signum()));
+    }
+    if is_mouse_button_down(MouseButton::Middle) {
+        let (dx, dy) = sub2d(ms.pos, xy_last);
+        camera.pan(dx, dy);
+    }
+}
+
 // TODO: merge to mouse_up_event
+/// `slices` are the projected cross-sections shown instead of the objects in slice view.
 pub fn lmb_up_event(
-    buttons: &mut Vec<Button>,
-    objects: &mut Vec<Object>,
-    windows: &mut WindowGroup,
+    buttons:      &mut Vec<Button>,
+    objects:      &mut Vec<Object>,
+    rotor:        &mut Rotor,
+    axes:         &Axes,
+    windows:      &mut WindowGroup,
+    slices:       Option<&Vec<Object>>,
+    camera:       &mut Camera,
+    obj_settings: &mut ObjSettings,
+    transition:   &mut Option<ViewTransition>,
+    turntable:    &mut Turntable,
 ) {
-    for btn in buttons {
+    let mut toggle_menu = false;
+    let mut toggle_obj = false;
+    let mut toggle_turntable = false;
+    let mut import_obj = false;
+    for btn in buttons.iter_mut() {
         if btn.is_active() && btn.is_click_button() {
             btn.set_active(false);
             match btn.get_type() {
-                ButtonType::Export =>  save(objects),
+                ButtonType::Export =>  save(objects, &rotor.to_angle()),
+                ButtonType::ExportSvg => {
+                    let (w, h) = windows.main.size();
+                    let show_vertices = match windows.main.buttons() {
+                        Some(btns) => btns[0].is_active(),
+                        None => true,
+                    };
+                    export_svg(slices.unwrap_or(objects), axes, (w, h), show_vertices);
+                },
                 ButtonType::Import => { match open_4dp() {
-                    Ok(obj) => {
-                        objects.clear();
-                        objects.push(obj);
+                    Ok((objs, a)) => {
+                        *objects = objs;
+                        *rotor = Rotor::from(a);
                     }, Err(e) => println!("{}", e),
                 } },
                 ButtonType::Info => instructions_event(windows),
+                ButtonType::ObjMenu => toggle_obj = true,
+                ButtonType::Obj(ObjMenuItem::Import) => {
+                    import_obj = true;
+                    toggle_obj = true;
+                },
+                ButtonType::Obj(item) => obj_settings.click(item, is_key_down(KeyCode::LeftShift)),
+                ButtonType::Projection => projection_event(camera, objects, rotor),
+                ButtonType::ViewMenu => toggle_menu = true,
+                ButtonType::View(preset) => {
+                    view_event(preset, objects, rotor, camera, transition);
+                    toggle_menu = true;
+                },
+                ButtonType::TurntableMenu => toggle_turntable = true,
+                ButtonType::Turntable(item) => turntable.click(item, is_key_down(KeyCode::LeftShift)),
                 _ => {},
             }
         }
     }
+    if toggle_menu { toggle_view_menu(buttons); }
+    if toggle_obj { toggle_obj_menu(buttons); }
+    if toggle_turntable { toggle_turntable_menu(buttons); }
     let mut hide_start_window = false;
+    let mut created = None;
     if let Some(btns) = windows.start.buttons_mut() {
         for btn in btns {
             if !btn.is_active() { continue; }
             btn.set_active(false);
             match btn.get_type() {
-                ButtonType::CreateTesseract => {
-                    objects.clear();
-                    objects.push(Object::tesseract());
-                    windows.main.show();
-                    hide_start_window = true;
-                    windows.instructions.hide();
-                },
-                ButtonType::CreateSphere3D => {
-                    objects.clear();
-                    objects.push(Object::sphere3d());
-                    windows.main.show();
-                    hide_start_window = true;
-                    windows.instructions.hide();
-                },
+                ButtonType::CreateTesseract => created = Some(Object::tesseract()),
+                ButtonType::CreateSphere3D => created = Some(Object::sphere3d()),
+                ButtonType::CreateCell5 => created = Some(Object::cell5()),
+                ButtonType::CreateCell16 => created = Some(Object::cell16()),
+                ButtonType::CreateCell24 => created = Some(Object::cell24()),
+                ButtonType::CreateCell120 => created = Some(Object::cell120()),
+                ButtonType::CreateCell600 => created = Some(Object::cell600()),
+                ButtonType::ImportObj => import_obj = true,
                 ButtonType::Close => {
                     let mut object = Object::empty();
                     object.vertices.push(Vec4f::new0());
@@ -94,6 +166,19 @@ pub fn lmb_up_event(
             }
         }
     }
+    if import_obj {
+        match open_obj(obj_settings.placement()) {
+            Ok(obj) => created = Some(obj),
+            Err(e) => println!("{}", e),
+        }
+    }
+    if let Some(obj) = created {
+        objects.clear();
+        objects.push(obj);
+        windows.main.show();
+        hide_start_window = true;
+        windows.instructions.hide();
+    }
     if hide_start_window { windows.start.hide(); }
 }
 
@@ -107,20 +192,79 @@ pub fn instructions_event(windows: &mut WindowGroup) {
     }
 }
 
+/// Switches to the next 4D→3D projection, or 3D→2D one with SHIFT held.
+/// A Schlegel diagram is taken through the selected cell if there is one.
+pub fn projection_event(camera: &mut Camera, objects: &Vec<Object>, rotor: &Rotor) {
+    if is_key_down(KeyCode::LeftShift) {
+        camera.projection3d = camera.projection3d.next();
+        return;
+    }
+    camera.projection = camera.projection.next();
+    if camera.projection == Projection::Schlegel {
+        if let Some(normal) = objects.iter().find_map(|o| o.selection_normal()) {
+            camera.face(rotor.apply(normal));
+        }
+    }
+}
+
+/// Starts turning the camera to the preset, projections switch at once.
+pub fn view_event(
+    preset:     ViewPreset,
+    objects:    &Vec<Object>,
+    rotor:      &Rotor,
+    camera:     &mut Camera,
+    transition: &mut Option<ViewTransition>,
+) {
+    let mut target = *camera;
+    if !preset.apply(objects, rotor, &mut target) {
+        println!("{}: вид не найден", preset.name());
+        return;
+    }
+    camera.projection = target.projection;
+    camera.projection3d = target.projection3d;
+    *transition = Some(ViewTransition::new(*camera, target));
+}
+
 pub fn catch_keyboard_event(
     objects:     &mut Vec<Object>,
     clipboard:   &mut Object,
     motion_axes: &mut MotionAxes,
+    slice:       &mut SliceView,
+    camera:      &mut Camera,
+    rotor:       &Rotor,
+    transition:  &mut Option<ViewTransition>,
+    turntable:   &mut Turntable,
 ) {
-    if is_key_pressed(KeyCode::E) {
-        extrude_event(objects);
+    let presets = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5];
+    if is_key_pressed(KeyCode::O) {
+        projection_event(camera, objects, rotor);
+    } else if is_key_pressed(KeyCode::R) {
+        turntable.enabled = !turntable.enabled;
+        turntable.paused = false;
+    } else if is_key_pressed(KeyCode::Space) {
+        turntable.click(TurntableItem::Pause, false);
+    } else if let Some(i) = presets.iter().position(|k| is_key_pressed(*k)) {
+        view_event(ViewPreset::ALL[i], objects, rotor, camera, transition);
+    } else if is_key_pressed(KeyCode::S) {
+        if is_key_down(KeyCode::LeftShift) { slice.next_axis(); }
+        else { slice.enabled = !slice.enabled; }
+    } else if is_key_pressed(KeyCode::E) {
+        extrude_event(objects, motion_axes);
     } else if is_key_pressed(KeyCode::Delete) {
         delete_event(objects, motion_axes);
     } else if is_key_pressed(KeyCode::F) {
-        fill_event(objects, motion_axes);
+        if is_key_down(KeyCode::LeftShift) { fill_face_event(objects, motion_axes); }
+        else if is_key_down(KeyCode::LeftControl) { fill_cell_event(objects, motion_axes); }
+        else { fill_event(objects, motion_axes); }
+    } else if is_key_pressed(KeyCode::H) {
+        hull_event(objects, motion_axes);
     } else if is_key_down(KeyCode::LeftControl) {
         if is_key_pressed(KeyCode::C) { copy_event(objects, clipboard); }
         else if is_key_pressed(KeyCode::V) { paste_event(objects, clipboard); }
+        else if is_key_pressed(KeyCode::D) { duplicate_event(objects, motion_axes); }
+        else if is_key_pressed(KeyCode::P) { combine_event(objects, motion_axes, Object::product); }
+        else if is_key_pressed(KeyCode::T) { combine_event(objects, motion_axes, Object::tegum); }
+        else if is_key_pressed(KeyCode::J) { join_event(objects, motion_axes); }
     }
 }
 
@@ -138,6 +282,46 @@ pub fn fill_event(
     motion_axes.move_to(get_center(objects));
 }
 
+/// Fills the selected closed edge loop of every object with a face.
+pub fn fill_face_event(
+    objects:     &mut Vec<Object>,
+    motion_axes: &mut MotionAxes,
+) {
+    for obj in objects.iter_mut() { obj.fill_face(); }
+    motion_axes.move_to(get_center(objects));
+}
+
+/// Fills the selected closed set of faces of every object with a cell.
+pub fn fill_cell_event(
+    objects:     &mut Vec<Object>,
+    motion_axes: &mut MotionAxes,
+) {
+    for obj in objects.iter_mut() { obj.fill_cell(); }
+    motion_axes.move_to(get_center(objects));
+}
+
+/// Builds the convex hull of the selected vertices of every object,
+/// printing the progress for large point sets every `HULL_PROGRESS_STEP` percent.
+pub fn hull_event(
+    objects:     &mut Vec<Object>,
+    motion_axes: &mut MotionAxes,
+) {
+    for obj in objects.iter_mut() {
+        let indices = obj.get_selected_vertices();
+        if indices.len() < 2 { continue; }
+        let mut reported = 0;
+        obj.fill_hull(&indices, &mut |done, total| {
+            let steps = done * 100 / total.max(1) / HULL_PROGRESS_STEP;
+            if total >= HULL_PROGRESS_MIN && steps > reported {
+                reported = steps;
+                println!("convex hull: {}%", steps * HULL_PROGRESS_STEP);
+            }
+        });
+        for i in indices { obj.select_vertice(i); }
+    }
+    motion_axes.move_to(get_center(objects));
+}
+
 pub fn delete_event(
     objects:     &mut Vec<Object>,
     motion_axes: &mut MotionAxes,
@@ -200,25 +384,110 @@ pub fn paste_event(objects: &mut Vec<Object>, clipboard: &Object) {
     objects.push(new_data);
 }
 
-pub fn extrude_event(objects: &mut Vec<Object>) {
-    for i in 0..objects.len() {
-        let mut buffer = Object::empty();
-        let vertices_count = objects[i].vertices.len();
-        copy_event(&vec![objects[i].clone()], &mut buffer);
-        // objects[i].clear_selection();
-        buffer.select();
-        objects[i] += buffer;
-        let mut counter = 0;
-        for j in 0..vertices_count {
-            if objects[i].vertices[j].selected {
-                objects[i].edges.push(Edge::new(j, vertices_count + counter));
-                counter += 1;
-            }
+/// Duplicates the selection inside every object and grabs the copy
+/// along the motion axis under the cursor, see `follow_event`.
+pub fn duplicate_event(objects: &mut Vec<Object>, motion_axes: &mut MotionAxes) {
+    if selected_objects(objects).is_empty() { return; }
+    for obj in objects.iter_mut() {
+        if obj.vertices.iter().any(|v| v.selected) { obj.duplicate_selected(); }
+    }
+    let (x, y) = mouse_position();
+    motion_axes.grab_towards(x, y);
+}
+
+/// Moves the selection grabbed by `MotionAxes::grab_towards` with the cursor.
+/// Releasing LMB puts it down, RMB or Escape brings it back to the start.
+pub fn follow_event(
+    xy:          (f32, f32),
+    xy_last:     (f32, f32),
+    rotor:       &Rotor,
+    camera:      &Camera,
+    motion_axes: &mut MotionAxes,
+    objects:     &mut Vec<Object>,
+    window:      &Window,
+) {
+    let cancel = is_mouse_button_pressed(MouseButton::Right) || is_key_pressed(KeyCode::Escape);
+    let delta = if cancel {
+        motion_axes.moved * -1.0
+    } else {
+        motion_axes.get_motion_delta(sub2d(xy, xy_last), rotor, camera, window)
+    };
+    motion_axes.moved += delta;
+    for obj in objects.iter_mut() {
+        for v in &mut obj.vertices {
+            if v.selected { *v += delta; }
         }
-        objects[i].deselect_first_n_vertices(vertices_count);
+    }
+    if cancel || is_mouse_button_released(MouseButton::Left) {
+        motion_axes.ungrab();
+        motion_axes.move_to(get_center(objects));
     }
 }
 
+fn selected_objects(objects: &Vec<Object>) -> Vec<usize> {
+    (0..objects.len())
+        .filter(|i| objects[*i].vertices.iter().any(|v| v.selected))
+        .collect()
+}
+
+/// Replaces the objects with selected vertices by their combination,
+/// e.g. `Object::product`, applied from left to right.
+pub fn combine_event(
+    objects:     &mut Vec<Object>,
+    motion_axes: &mut MotionAxes,
+    combine:     fn(&Object, &Object) -> Object,
+) {
+    let selected = selected_objects(objects);
+    if selected.len() < 2 { return; }
+    let mut res = objects[selected[0]].clone();
+    for i in &selected[1..] {
+        res = combine(&res, &objects[*i]);
+    }
+    for i in selected.iter().rev() {
+        objects.remove(*i);
+    }
+    res.select();
+    objects.push(res);
+    motion_axes.move_to(get_center(objects));
+}
+
+/// Joins the selected objects, a single selected object becomes
+/// a pyramid with the apex along W from its center.
+pub fn join_event(objects: &mut Vec<Object>, motion_axes: &mut MotionAxes) {
+    let selected = selected_objects(objects);
+    if selected.len() != 1 { return combine_event(objects, motion_axes, Object::join); }
+    let obj = &mut objects[selected[0]];
+    let mut center = Vec4f::new0();
+    for v in &obj.vertices { center += *v; }
+    center = center / obj.vertices.len() as f32;
+    let height = obj.vertices.iter().map(|v| dist(*v, center)).fold(1.0, f32::max);
+    *obj = obj.pyramid(center + Vec4f::new(0.0, 0.0, 0.0, height));
+    obj.select();
+    motion_axes.move_to(get_center(objects));
+}
+
+/// Extrudes the selection of every object along its default direction
+/// (see `Object::extrude_direction`) by the average length of the selected
+/// edges, then grabs the copy along the motion axis under the cursor.
+pub fn extrude_event(objects: &mut Vec<Object>, motion_axes: &mut MotionAxes) {
+    if selected_objects(objects).is_empty() { return; }
+    for obj in objects.iter_mut() {
+        if !obj.vertices.iter().any(|v| v.selected) { continue; }
+        let lengths: Vec<f32> = obj.edges
+            .iter()
+            .filter(|e| e.selected)
+            .map(|e| dist(obj.vertices[e.a], obj.vertices[e.b]))
+            .collect();
+        let distance = if lengths.is_empty() { 1.0 } else { lengths.iter().sum::<f32>() / lengths.len() as f32 };
+        let direction = obj.extrude_direction();
+        obj.extrude(direction, distance);
+    }
+    let (x, y) = mouse_position();
+    motion_axes.grab_towards(x, y);
+    if motion_axes.grabbed { motion_axes.grab_start = get_center(objects); }
+    else { motion_axes.move_to(get_center(objects)); }
+}
+
 pub fn mouse_move_event(
     xy:         (f32, f32),
     motion_axes: &mut MotionAxes,
@@ -274,10 +543,13 @@ pub fn mouse_up_event(
     motion_axes.pos = get_center(objects);
 }
 
+/// Moves the selection along the grabbed motion axis, or turns the objects
+/// with an arcball: the cursor drags a point of a 4D ball under the viewport.
 pub fn drag_event(
     xy:          (f32, f32),
     xy_last:     (f32, f32),
-    angle:        &mut Angle,
+    rotor:        &mut Rotor,
+    camera:       &Camera,
     scroll_delta: f32,
     motion_axes:  &mut MotionAxes,
     objects:      &mut Vec<Object>,
@@ -285,7 +557,7 @@ pub fn drag_event(
 ) {
     if motion_axes.grabbed {
         if motion_axes.grabbed && is_mouse_button_down(MouseButton::Right) {
-            let delta = motion_axes.get_motion_delta(sub2d(xy, xy_last), angle, window);
+            let delta = motion_axes.get_motion_delta(sub2d(xy, xy_last), rotor, camera, window);
             for obj in objects {
                 for v in &mut obj.vertices {
                     if v.selected {
@@ -296,18 +568,26 @@ pub fn drag_event(
         }
         return;
     }
-    let x_delta = (xy.0 - xy_last.0) / 200.0;
-    let y_delta = (xy.1 - xy_last.1) / 200.0;
-    if is_key_down(KeyCode::LeftShift) {
-        angle.yz += y_delta;
-        angle.xz += x_delta;
-    } else {
-        angle.yw += y_delta;
-        angle.xw += x_delta;
-    }
-    angle.yz = angle.yz.max(-PI / 2.0).min(PI / 2.0);
-    angle.yw = angle.yw.max(-PI / 2.0).min(PI / 2.0);
-    angle.zw += scroll_delta / 100.0;
+    if xy == xy_last && scroll_delta == 0.0 { return; }
+    let [right, up, over, forward] = camera.basis();
+    // the depth of the arcball points to the viewer along W, or along Z with SHIFT
+    let depth = if is_key_down(KeyCode::LeftShift) { over } else { forward * -1.0 };
+    let (x, y, w, h) = window.as_tuple();
+    let radius = w.min(h) / 2.0;
+    let on_ball = |p: (f32, f32)| {
+        let (sx, sy) = ((p.0 - x - w / 2.0) / radius, (p.1 - y - h / 2.0) / radius);
+        let r = (sx * sx + sy * sy).sqrt();
+        if r > 1.0 {
+            right * (sx / r) + up * (sy / r)
+        } else {
+            right * sx + up * sy + depth * (1.0 - r * r).sqrt()
+        }
+    };
+    // turns are composed after the current orientation, so they always
+    // follow the mouse and never lock
+    *rotor = (Rotor::in_plane(over, forward, scroll_delta / 100.0)
+        * Rotor::between(on_ball(xy_last), on_ball(xy))
+        * *rotor).norm();
 }
 
 pub fn lmb_click_event(
@@ -1,77 +1,185 @@
-use std::fs;
+use std::io;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use super::error::*;
-use crate::save::SData;
+use crate::objects::Edge;
+use crate::angle::Angle;
+use crate::save::MAGIC;
+use crate::save::VERSION;
+use crate::save::MAGIC_V1;
+use crate::save::ByteOrder;
+use crate::objects::Face;
+use crate::objects::Cell;
 use crate::objects::Vec4f;
-use std::convert::TryInto;
 use crate::objects::Object;
-use macroquad::prelude::show_mouse;
+use crate::json::load_json_from_path;
+use crate::off::load_off_from_path;
+use crate::dialog::show_dialog;
 
 fn open_with_explorer() -> Option<String> {
-	show_mouse(true);
-	let path = rfd::FileDialog::new()
+	show_dialog(|| rfd::FileDialog::new()
 		.add_filter("4D object", &["4dp"])
+		.add_filter("4D object (JSON)", &["json"])
+		.add_filter("4OFF", &["off"])
 		.add_filter("4D object", &["*"])
 		.set_directory("/")
         .set_title("Открыть как")
-		.pick_file();
-	show_mouse(false);
-	match path {
-		Some(p) => p.as_os_str().to_str().map(|s| s.to_string()),
-		None => None,
-	}
+		.pick_file())
 }
 
-fn cut(bytes: &mut Vec<u8>, count: u32) -> Vec<u8> {
-    let mut res = vec![];
-    for _ in 0..count {
-        res.push(bytes.remove(0));
+/// Reads a scene written by `save::write_to`. Both the current layout (version 2)
+/// and legacy version 1 files are accepted, see `save::write_to` for the structure.
+/// Every read is bounds-checked and every index is validated against the
+/// declared counts, the first problem is reported as `ImportError::Malformed`.
+pub fn read_from<R: Read>(reader: &mut R) -> Result<(Vec<Object>, Angle), Error> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes).map_err(Error::Io)?;
+    if bytes.len() < 4 { return Err(Error::Import(ImportError::InvalidExtention)); }
+    let magic = &bytes[..4];
+    if magic == MAGIC_V1 { return open_4dp_v1(&bytes); }
+    if magic != MAGIC { return Err(Error::Import(ImportError::InvalidExtention)); }
+    let mut cursor = Cursor::new(&bytes, ByteOrder::Little);
+    cursor.take(4)?;
+    let marker_offset = cursor.pos;
+    cursor.order = match ByteOrder::from_marker(cursor.u8()?) {
+        Some(order) => order,
+        None => return Err(cursor.error(marker_offset, "unknown byte order")),
+    };
+    cursor.u8()?; // reserved
+    let version = cursor.u16()?;
+    if version != VERSION {
+        return Err(Error::Import(ImportError::UnsupportedVersion(version)));
     }
-    return res;
+    cursor.element = Element::Angle;
+    let mut angle = Angle::new();
+    angle.xy = cursor.f32()?;
+    angle.xz = cursor.f32()?;
+    angle.xw = cursor.f32()?;
+    angle.yz = cursor.f32()?;
+    angle.yw = cursor.f32()?;
+    angle.zw = cursor.f32()?;
+    cursor.element = Element::Counts;
+    let objects_count = cursor.count(OBJECT_MIN_SIZE)?;
+    let mut objects = Vec::with_capacity(objects_count);
+    for _ in 0..objects_count { objects.push(read_object(&mut cursor, version)?); }
+    Ok((objects, angle))
+}
+
+/// Opens a file for reading, a missing file is reported as `ImportError::FileNotFound`.
+pub fn open_file<P: AsRef<Path>>(path: P) -> Result<File, Error> {
+    File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::Import(ImportError::FileNotFound),
+        _ => Error::Io(e),
+    })
+}
+
+pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<(Vec<Object>, Angle), Error> {
+    read_from(&mut open_file(path)?)
 }
 
-fn deserialize_u64_from_bytes(bytes: &mut Vec<u8>) -> Result<u64, Error> {
-    match DData::Uint(cut(bytes, 8)).deserialize()? {
-        SData::Ulong(v) => Ok(v),
-        _ => return Err(Error::Import(ImportError::FileCorrupted)),
+/// Loads a scene choosing the format by extention: ".json" files are read
+/// with `json::read_json`, ".off" with `off::read_off`, everything else with `read_from`.
+pub fn load_scene_from_path<P: AsRef<Path>>(path: P) -> Result<(Vec<Object>, Angle), Error> {
+    match path.as_ref().extension().and_then(|e| e.to_str()) {
+        Some("json") => load_json_from_path(path),
+        Some("off") => Ok((vec![load_off_from_path(path)?], Angle::new())),
+        _ => load_from_path(path),
     }
 }
 
-/// File structure
-/// First 8 bytes: 0x4d4d4d4d (extention)
-/// Next 8 bytes: vertices count (a)
-/// Next 8 bytes: edges count (b)
-/// Next 8 bytes: faces count (c)
-/// Next 8 bytes: cells count (d)
-/// Next 8 bytes: XW angle
-/// Next 8 bytes: YW angle
-/// Next 8 bytes: ZW angle
-/// Next a * 4 * 8 bytes (vertices): f32, f32, f32, f32 - coordinates
-/// Next b * 2 * 8 bytes (edges): u32, u32 - vertices indices
-/// Next c * 6 * 8 bytes (faces): u32, u32, u32 - vertices indices and u32, u32, u32 - edges indices
-/// Next d * 14 * 8 bytes (cells): u32 (4 times) - vertices indices, u32 (6 times) - edges, u32 (4 times) - faces
-pub fn open_4dp() -> Result<Object, Error> {
+/// Asks for a file and loads the scene from it, see `load_scene_from_path`.
+pub fn open_4dp() -> Result<(Vec<Object>, Angle), Error> {
     let path = match open_with_explorer() {
         Some(p) => p,
         None => return Err(Error::Cancel),
     };
-    let mut bytes = match fs::read(path) {
-        Ok(b) => b,
-        Err(_) => return Err(Error::Import(ImportError::FileNotFound)),
-    };
-    let extention = cut(&mut bytes, 8);
-    if hex::encode(&extention) != "4d4d4d4d" { return Err(Error::Import(ImportError::InvalidExtention)); }
-    let vertices_count = deserialize_u64_from_bytes(&mut bytes)?;
-    let edges_count = deserialize_u64_from_bytes(&mut bytes)?;
-    let faces_count = deserialize_u64_from_bytes(&mut bytes)?;
-    let cells_count = deserialize_u64_from_bytes(&mut bytes)?;
-    let mut vertices = vec![];
-    let mut edges = vec![];
-    let mut faces = vec![];
-    let mut cells = vec![];
-    for _ in 0..vertices_count { vertices.push(DData::Vector(cut(&mut bytes, 32)).deserialize()?.as_vector()?) }
-    for _ in 0..edges_count { edges.push(DData::Vector(cut(&mut bytes, 16)).deserialize()?.as_edge()?); }
-    for _ in 0..faces_count { faces.push(DData::Vector(cut(&mut bytes, 48)).deserialize()?.as_face()?); }
-    for _ in 0..cells_count { cells.push(DData::Vector(cut(&mut bytes, 112)).deserialize()?.as_cell()?); }
+    load_scene_from_path(path)
+}
+
+fn merge(objects: Vec<Object>) -> Object {
+    if objects.len() == 1 { return objects.into_iter().next().unwrap(); }
+    let mut merged = Object::empty();
+    for obj in objects { merged += obj; }
+    merged
+}
+
+impl Object {
+    /// Reads a scene and merges all its objects into one.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Object, Error> {
+        read_from(reader).map(|(objects, _)| merge(objects))
+    }
+
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Object, Error> {
+        load_from_path(path).map(|(objects, _)| merge(objects))
+    }
+}
+
+/// Version 1: "MMMM" magic, then counts and records, all big endian.
+fn open_4dp_v1(bytes: &[u8]) -> Result<(Vec<Object>, Angle), Error> {
+    let mut cursor = Cursor::new(bytes, ByteOrder::Big);
+    cursor.take(4)?;
+    let obj = read_topology(&mut cursor, 1)?;
+    Ok((vec![obj], Angle::new()))
+}
+
+/// Name length and four element counts.
+const OBJECT_MIN_SIZE: usize = 4 + 4 * 4;
+
+fn read_object(cursor: &mut Cursor, version: u16) -> Result<Object, Error> {
+    cursor.element = Element::Name;
+    let name_len = cursor.u32()? as usize;
+    let offset = cursor.pos;
+    let name = std::str::from_utf8(cursor.take(name_len)?)
+        .map_err(|_| cursor.error(offset, "name is not valid UTF-8"))?
+        .to_string();
+    let mut obj = read_topology(cursor, version)?;
+    if !name.is_empty() { obj.name = Some(name); }
+    Ok(obj)
+}
+
+fn read_topology(cursor: &mut Cursor, version: u16) -> Result<Object, Error> {
+    let (face_size, cell_size) = if version == 1 { (24, 56) } else { (4, 12) };
+    cursor.element = Element::Counts;
+    let vertices_count = cursor.count(16)?;
+    let edges_count = cursor.count(8)?;
+    let faces_count = cursor.count(face_size)?;
+    let cells_count = cursor.count(cell_size)?;
+    let mut vertices = Vec::with_capacity(vertices_count);
+    let mut edges = Vec::with_capacity(edges_count);
+    let mut faces = Vec::with_capacity(faces_count);
+    let mut cells = Vec::with_capacity(cells_count);
+    let (vc, ec, fc) = (vertices_count, edges_count, faces_count);
+
+    cursor.element = Element::Vertex;
+    for _ in 0..vertices_count {
+        let (x, y, z, w) = (cursor.f32()?, cursor.f32()?, cursor.f32()?, cursor.f32()?);
+        vertices.push(Vec4f::new(x, y, z, w));
+    }
+    cursor.element = Element::Edge;
+    for _ in 0..edges_count {
+        let a = cursor.index(vc, "vertex index out of range")?;
+        let b = cursor.index(vc, "vertex index out of range")?;
+        edges.push(Edge::new(a, b));
+    }
+    cursor.element = Element::Face;
+    for _ in 0..faces_count {
+        let offset = cursor.pos;
+        let n = if version == 1 { 3 } else { cursor.count(8)? };
+        if n < 3 { return Err(cursor.error(offset, "face has less than 3 vertices")); }
+        let vertices = cursor.indices(n, vc, "vertex index out of range")?;
+        let edges = cursor.indices(n, ec, "edge index out of range")?;
+        faces.push(Face::new(vertices, edges));
+    }
+    cursor.element = Element::Cell;
+    for _ in 0..cells_count {
+        let (p, q, r) = if version == 1 { (4, 6, 4) }
+            else { (cursor.count(4)?, cursor.count(4)?, cursor.count(4)?) };
+        let vertices = cursor.indices(p, vc, "vertex index out of range")?;
+        let edges = cursor.indices(q, ec, "edge index out of range")?;
+        let faces = cursor.indices(r, fc, "face index out of range")?;
+        cells.push(Cell::new(vertices, edges, faces));
+    }
     Ok(Object {
         vertices,
         edges,
@@ -81,56 +189,274 @@ pub fn open_4dp() -> Result<Object, Error> {
     })
 }
 
-#[derive(Debug, Clone)]
-pub enum DData {
-    Vector(Vec<u8>), // 32 bytes
-    Edge(Vec<u8>), // 8
-    Face(Vec<u8>), // 24
-    Cell(Vec<u8>), // 56
-    Uint(Vec<u8>), // 8
-    Float(Vec<u8>), // 8
+/// Bounds-checked reader over a binary scene. A failed read points at the
+/// byte where it started and at the element being read.
+struct Cursor<'a> {
+    bytes:   &'a [u8],
+    pos:     usize,
+    order:   ByteOrder,
+    element: Element,
 }
 
-fn cut_float(bytes: &mut Vec<u8>) -> Result<f32, Error> {
-    Ok(f32::from_be_bytes(
-        cut(bytes, 4)
-            .as_slice()
-            .try_into()
-            .map_err(|_| Error::Import(ImportError::FileCorrupted))?
-    ))
-}
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8], order: ByteOrder) -> Self {
+        Cursor { bytes, pos: 0, order, element: Element::Header }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn error(&self, offset: usize, reason: &'static str) -> Error {
+        Error::Import(ImportError::Malformed { offset, element: self.element, reason })
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.remaining() { return Err(self.error(self.pos, "unexpected end of file")); }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut b = [0; N];
+        b.copy_from_slice(self.take(N)?);
+        Ok(b)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let b = self.array()?;
+        Ok(match self.order {
+            ByteOrder::Little => u16::from_le_bytes(b),
+            ByteOrder::Big    => u16::from_be_bytes(b),
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let b = self.array()?;
+        Ok(match self.order {
+            ByteOrder::Little => u32::from_le_bytes(b),
+            ByteOrder::Big    => u32::from_be_bytes(b),
+        })
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        let b = self.array()?;
+        Ok(match self.order {
+            ByteOrder::Little => f32::from_le_bytes(b),
+            ByteOrder::Big    => f32::from_be_bytes(b),
+        })
+    }
+
+    /// Reads a count of records at least `record_size` bytes long each.
+    /// Counts that can't fit in the rest of the file are rejected
+    /// before anything is allocated for them.
+    fn count(&mut self, record_size: usize) -> Result<usize, Error> {
+        let offset = self.pos;
+        let count = self.u32()? as usize;
+        if count.saturating_mul(record_size) > self.remaining() {
+            return Err(self.error(offset, "count exceeds the file size"));
+        }
+        Ok(count)
+    }
+
+    fn index(&mut self, limit: usize, reason: &'static str) -> Result<usize, Error> {
+        let offset = self.pos;
+        let index = self.u32()? as usize;
+        if index >= limit { return Err(self.error(offset, reason)); }
+        Ok(index)
+    }
 
-fn cut_u64(bytes: &mut Vec<u8>) -> Result<u64, Error> {
-    Ok(u64::from_be_bytes(
-        cut(bytes, 8)
-            .as_slice()
-            .try_into()
-            .map_err(|_| Error::Import(ImportError::FileCorrupted))?
-    ))
+    fn indices(&mut self, n: usize, limit: usize, reason: &'static str) -> Result<Vec<usize>, Error> {
+        let mut indices = Vec::with_capacity(n);
+        for _ in 0..n { indices.push(self.index(limit, reason)?); }
+        Ok(indices)
+    }
 }
 
-impl DData {
-    fn deserialize(&self) -> Result<SData, Error> {
-        match self {
-            DData::Vector(b) => {
-                let mut bytes = b.clone();
-                let x = cut_float(&mut bytes)?;
-                let y = cut_float(&mut bytes)?;
-                let z = cut_float(&mut bytes)?;
-                let w = cut_float(&mut bytes)?;
-                return Ok(SData::Vector(Vec4f::new(x, y, z, w)));
-            },
-            DData::Edge(b) => {
-                let mut bytes = b.clone();
-                return Ok(SData::Edge(
-                    cut_u64(&mut bytes)? as usize,
-                    cut_u64(&mut bytes)? as usize
-                ));
-            },
-            DData::Face(b) => todo!(),
-            DData::Cell(b) => todo!(),
-            DData::Uint(b) => return Ok(SData::Ulong(u64::from_be_bytes(b.as_slice().try_into().unwrap()))),
-            DData::Float(b) => return Ok(SData::Float(f32::from_be_bytes(b.as_slice().try_into().unwrap()))),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::write_to;
+
+    fn load(bytes: &[u8]) -> Result<(Vec<Object>, Angle), Error> {
+        read_from(&mut &bytes[..])
+    }
+
+    fn scene(bytes: &[u8]) -> (Vec<Object>, Angle) {
+        match load(bytes) {
+            Ok(scene) => scene,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn is_malformed(res: Result<(Vec<Object>, Angle), Error>) -> bool {
+        matches!(res, Err(Error::Import(ImportError::Malformed { .. })))
+    }
+
+    fn angles(a: &Angle) -> [f32; 6] {
+        [a.xy, a.xz, a.xw, a.yz, a.yw, a.zw]
+    }
+
+    /// Square pyramid in the W = 1 hyperplane with all its faces and its cell.
+    fn pyramid() -> Object {
+        let mut obj = Object::empty();
+        obj.vertices = vec![
+            Vec4f::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4f::new( 1.0, -1.0, 0.0, 1.0),
+            Vec4f::new( 1.0,  1.0, 0.0, 1.0),
+            Vec4f::new(-1.0,  1.0, 0.0, 1.0),
+            Vec4f::new( 0.0,  0.0, 1.0, 1.0),
+        ];
+        obj.add_face(vec![0, 1, 2, 3]);
+        for i in 0..4 { obj.add_face(vec![i, (i + 1) % 4, 4]); }
+        obj.add_cell((0..5).collect());
+        obj.name = Some("Пирамида".to_string());
+        obj
+    }
+
+    fn saved(objects: &Vec<Object>, angle: &Angle) -> Vec<u8> {
+        let mut bytes = vec![];
+        assert!(write_to(&mut bytes, objects, angle).is_ok());
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut unnamed = pyramid();
+        unnamed.name = None;
+        let objects = vec![pyramid(), unnamed, Object::empty()];
+        let mut angle = Angle::new();
+        angle.xy = 0.5;
+        angle.yw = -1.25;
+        angle.zw = 3.0;
+        let (loaded, loaded_angle) = scene(&saved(&objects, &angle));
+        assert_eq!(angles(&loaded_angle), angles(&angle));
+        assert_eq!(loaded.len(), objects.len());
+        for (l, o) in loaded.iter().zip(objects.iter()) {
+            assert_eq!(l.name, o.name);
+            assert_eq!(l.vertices, o.vertices);
+            let pairs = |obj: &Object| obj.edges.iter().map(|e| (e.a, e.b)).collect::<Vec<_>>();
+            assert_eq!(pairs(l), pairs(o));
+            assert_eq!(l.faces, o.faces);
+            assert_eq!(l.cells, o.cells);
+        }
+    }
+
+    #[test]
+    fn v1() {
+        let mut bytes = MAGIC_V1.to_vec();
+        for count in [2u32, 1, 0, 0] { bytes.extend_from_slice(&count.to_be_bytes()); }
+        for c in [0.0f32, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0] { bytes.extend_from_slice(&c.to_be_bytes()); }
+        for i in [0u32, 1] { bytes.extend_from_slice(&i.to_be_bytes()); }
+        let (objects, angle) = scene(&bytes);
+        assert_eq!(angles(&angle), [0.0; 6]);
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].vertices[1], Vec4f::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!((objects[0].edges[0].a, objects[0].edges[0].b), (0, 1));
+
+        assert!(is_malformed(load(&MAGIC_V1)));
+        assert!(is_malformed(load(&bytes[..bytes.len() - 1])));
+    }
+
+    #[test]
+    fn missing_file() {
+        let res = load_from_path("/nonexistent/scene.4dp");
+        assert!(matches!(res, Err(Error::Import(ImportError::FileNotFound))));
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = saved(&vec![pyramid()], &Angle::new());
+        for len in MAGIC.len()..bytes.len() {
+            assert!(is_malformed(load(&bytes[..len])), "cut at {} bytes", len);
         }
     }
-}
\ No newline at end of file
+
+    /// Header of a little endian file of the given version with a zero angle.
+    fn header(version: u16) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(ByteOrder::Little.marker());
+        bytes.push(0);
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&[0; 6 * 4]);
+        bytes
+    }
+
+    fn push(bytes: &mut Vec<u8>, values: &[u32]) {
+        for v in values { bytes.extend_from_slice(&v.to_le_bytes()); }
+    }
+
+    fn push_vertices(bytes: &mut Vec<u8>, count: usize) {
+        for i in 0..count * 4 { bytes.extend_from_slice(&(i as f32).to_le_bytes()); }
+    }
+
+    fn malformed(bytes: &[u8]) -> (usize, Element, &'static str) {
+        match load(bytes) {
+            Err(Error::Import(ImportError::Malformed { offset, element, reason })) => (offset, element, reason),
+            Err(e) => panic!("{}", e),
+            Ok(_) => panic!("malformed file loaded"),
+        }
+    }
+
+    /// One v2 object with an empty name and the given counts, the records start at byte 56.
+    fn v2(counts: [u32; 4]) -> Vec<u8> {
+        let mut bytes = header(2);
+        push(&mut bytes, &[1, 0]);
+        push(&mut bytes, &counts);
+        bytes
+    }
+
+    #[test]
+    fn unsupported_version() {
+        for version in [0, 3, 4, 5] {
+            let mut bytes = header(version);
+            push(&mut bytes, &[0]);
+            let res = load(&bytes);
+            assert!(matches!(res, Err(Error::Import(ImportError::UnsupportedVersion(v))) if v == version));
+        }
+    }
+
+    #[test]
+    fn truncated_record() {
+        let mut bytes = v2([1, 0, 0, 0]);
+        push_vertices(&mut bytes, 1);
+        bytes.truncate(56 + 8);
+        assert_eq!(malformed(&bytes), (64, Element::Vertex, "unexpected end of file"));
+    }
+
+    #[test]
+    fn oversized_count() {
+        let mut bytes = header(2);
+        push(&mut bytes, &[u32::MAX]);
+        assert_eq!(malformed(&bytes), (32, Element::Counts, "count exceeds the file size"));
+
+        let mut bytes = v2([u32::MAX, 0, 0, 0]);
+        push_vertices(&mut bytes, 1);
+        assert_eq!(malformed(&bytes), (40, Element::Counts, "count exceeds the file size"));
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        let mut bytes = v2([2, 1, 0, 0]);
+        push_vertices(&mut bytes, 2);
+        push(&mut bytes, &[0, 2]);
+        assert_eq!(malformed(&bytes), (92, Element::Edge, "vertex index out of range"));
+
+        let mut bytes = v2([3, 3, 1, 0]);
+        push_vertices(&mut bytes, 3);
+        push(&mut bytes, &[0, 1, 1, 2, 2, 0]);
+        push(&mut bytes, &[3, 0, 1, 3, 0, 1, 2]);
+        assert_eq!(malformed(&bytes), (140, Element::Face, "vertex index out of range"));
+
+        let mut bytes = v2([3, 3, 1, 0]);
+        push_vertices(&mut bytes, 3);
+        push(&mut bytes, &[0, 1, 1, 2, 2, 0]);
+        push(&mut bytes, &[3, 0, 1, 2, 0, 1, 3]);
+        assert_eq!(malformed(&bytes), (152, Element::Face, "edge index out of range"));
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use serde::Serialize;
+use serde::Deserialize;
+use crate::error::*;
+use crate::angle::Angle;
+use crate::import::open_file;
+use crate::objects::Edge;
+use crate::objects::Face;
+use crate::objects::Cell;
+use crate::objects::Vec4f;
+use crate::objects::Object;
+
+pub const JSON_VERSION: u32 = 1;
+
+/// Text layout of a scene, e.g.
+/// ```json
+/// {
+///   "version": 1,
+///   "angle": { "xy": 0.0, "xz": 0.0, "xw": 0.0, "yz": 0.0, "yw": 0.0, "zw": 0.0 },
+///   "objects": [{
+///     "name": "Triangle",
+///     "vertices": [[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 1]],
+///     "edges": [[0, 1], [1, 2], [2, 0]],
+///     "faces": [{ "vertices": [0, 1, 2], "edges": [0, 1, 2] }],
+///     "cells": []
+///   }]
+/// }
+/// ```
+/// Indices are local to the object they belong to. `name`, `angle`, `faces`
+/// and `cells` may be omitted, so may edges of a face and vertices and edges
+/// of a cell: they are derived from the face loop and the cell faces.
+#[derive(Serialize, Deserialize)]
+struct SceneDoc {
+    version: u32,
+    #[serde(default = "Angle::new")]
+    angle:   Angle,
+    objects: Vec<ObjectDoc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ObjectDoc {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name:     Option<String>,
+    vertices: Vec<[f32; 4]>,
+    edges:    Vec<[usize; 2]>,
+    #[serde(default)]
+    faces:    Vec<FaceDoc>,
+    #[serde(default)]
+    cells:    Vec<CellDoc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FaceDoc {
+    vertices: Vec<usize>,
+    #[serde(default)]
+    edges:    Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CellDoc {
+    #[serde(default)]
+    vertices: Vec<usize>,
+    #[serde(default)]
+    edges:    Vec<usize>,
+    faces:    Vec<usize>,
+}
+
+impl ObjectDoc {
+    fn from_object(obj: &Object) -> Self {
+        ObjectDoc {
+            name:     obj.name.clone(),
+            vertices: obj.vertices.iter().map(|v| [v.x, v.y, v.z, v.w]).collect(),
+            edges:    obj.edges.iter().map(|e| [e.a, e.b]).collect(),
+            faces:    obj.faces.iter().map(|f| FaceDoc {
+                vertices: f.vertices.clone(),
+                edges:    f.edges.clone(),
+            }).collect(),
+            cells:    obj.cells.iter().map(|c| CellDoc {
+                vertices: c.vertices.clone(),
+                edges:    c.edges.clone(),
+                faces:    c.faces.clone(),
+            }).collect(),
+        }
+    }
+
+    fn into_object(self) -> Result<Object, Error> {
+        let (vc, ec, fc) = (self.vertices.len(), self.edges.len(), self.faces.len());
+        let valid = self.edges.iter().all(|e| e.iter().all(|i| *i < vc))
+            && self.faces.iter().all(|f| {
+                f.vertices.len() >= 3
+                    && (f.edges.is_empty() || f.edges.len() == f.vertices.len())
+                    && f.vertices.iter().all(|i| *i < vc)
+                    && f.edges.iter().all(|i| *i < ec)
+            })
+            && self.cells.iter().all(|c| {
+                c.vertices.iter().all(|i| *i < vc)
+                    && c.edges.iter().all(|i| *i < ec)
+                    && c.faces.iter().all(|i| *i < fc)
+            });
+        if !valid { return Err(Error::Import(ImportError::FileCorrupted)); }
+        let mut obj = Object::empty();
+        obj.name = self.name;
+        obj.vertices = self.vertices.iter().map(|v| Vec4f::new(v[0], v[1], v[2], v[3])).collect();
+        obj.edges = self.edges.iter().map(|e| Edge::new(e[0], e[1])).collect();
+        for f in self.faces {
+            if f.edges.is_empty() { obj.add_face(f.vertices); }
+            else { obj.faces.push(Face::new(f.vertices, f.edges)); }
+        }
+        for c in self.cells {
+            if c.vertices.is_empty() && c.edges.is_empty() { obj.add_cell(c.faces); }
+            else { obj.cells.push(Cell::new(c.vertices, c.edges, c.faces)); }
+        }
+        Ok(obj)
+    }
+}
+
+pub fn write_json<W: Write>(writer: &mut W, objects: &Vec<Object>, angle: &Angle) -> Result<(), Error> {
+    let doc = SceneDoc {
+        version: JSON_VERSION,
+        angle:   *angle,
+        objects: objects.iter().map(ObjectDoc::from_object).collect(),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &doc).map_err(|e| Error::Io(e.into()))?;
+    writer.write_all(b"\n").map_err(Error::Io)
+}
+
+pub fn read_json<R: Read>(reader: &mut R) -> Result<(Vec<Object>, Angle), Error> {
+    let doc: SceneDoc = serde_json::from_reader(reader).map_err(|e| {
+        if e.is_io() { Error::Io(e.into()) }
+        else { Error::Import(ImportError::Syntax(e.to_string())) }
+    })?;
+    if doc.version != JSON_VERSION {
+        return Err(Error::Import(ImportError::UnsupportedVersion(doc.version.min(u16::MAX as u32) as u16)));
+    }
+    let mut objects = vec![];
+    for obj in doc.objects { objects.push(obj.into_object()?); }
+    Ok((objects, doc.angle))
+}
+
+pub fn save_json_to_path<P: AsRef<Path>>(path: P, objects: &Vec<Object>, angle: &Angle) -> Result<(), Error> {
+    let mut file = File::create(path).map_err(Error::Io)?;
+    write_json(&mut file, objects, angle)
+}
+
+pub fn load_json_from_path<P: AsRef<Path>>(path: P) -> Result<(Vec<Object>, Angle), Error> {
+    read_json(&mut open_file(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &str) -> Result<(Vec<Object>, Angle), Error> {
+        read_json(&mut text.as_bytes())
+    }
+
+    /// Square pyramid in the W = 1 hyperplane with all its faces and its cell.
+    fn pyramid() -> Object {
+        let mut obj = Object::empty();
+        obj.vertices = vec![
+            Vec4f::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4f::new( 1.0, -1.0, 0.0, 1.0),
+            Vec4f::new( 1.0,  1.0, 0.0, 1.0),
+            Vec4f::new(-1.0,  1.0, 0.0, 1.0),
+            Vec4f::new( 0.0,  0.0, 0.5, 1.0),
+        ];
+        obj.add_face(vec![0, 1, 2, 3]);
+        for i in 0..4 { obj.add_face(vec![i, (i + 1) % 4, 4]); }
+        obj.add_cell((0..5).collect());
+        obj.name = Some("Пирамида".to_string());
+        obj
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut unnamed = pyramid();
+        unnamed.name = None;
+        let objects = vec![pyramid(), unnamed, Object::empty()];
+        let mut angle = Angle::new();
+        angle.xz = 0.25;
+        angle.yw = -1.5;
+        let mut text = vec![];
+        assert!(write_json(&mut text, &objects, &angle).is_ok());
+        let (loaded, loaded_angle) = match read_json(&mut &text[..]) {
+            Ok(scene) => scene,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!((loaded_angle.xz, loaded_angle.yw), (angle.xz, angle.yw));
+        assert_eq!(loaded.len(), objects.len());
+        for (l, o) in loaded.iter().zip(objects.iter()) {
+            assert_eq!(l.name, o.name);
+            assert_eq!(l.vertices, o.vertices);
+            let pairs = |obj: &Object| obj.edges.iter().map(|e| (e.a, e.b)).collect::<Vec<_>>();
+            assert_eq!(pairs(l), pairs(o));
+            assert_eq!(l.faces, o.faces);
+            assert_eq!(l.cells, o.cells);
+        }
+    }
+
+    #[test]
+    fn shorthand() {
+        let text = r#"{
+            "version": 1,
+            "objects": [{
+                "vertices": [[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]],
+                "edges": [],
+                "faces": [{ "vertices": [0, 1, 2] }, { "vertices": [0, 1, 3] }, { "vertices": [0, 2, 3] }, { "vertices": [1, 2, 3] }],
+                "cells": [{ "faces": [0, 1, 2, 3] }]
+            }]
+        }"#;
+        let (objects, angle) = match load(text) {
+            Ok(scene) => scene,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(angle.xy, 0.0);
+        assert_eq!((objects[0].edges.len(), objects[0].faces.len()), (6, 4));
+        assert_eq!((objects[0].cells[0].vertices.len(), objects[0].cells[0].edges.len()), (4, 6));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(load("{"), Err(Error::Import(ImportError::Syntax(_)))));
+        assert!(matches!(load(r#"{ "version": 2, "objects": [] }"#), Err(Error::Import(ImportError::UnsupportedVersion(2)))));
+        let text = r#"{ "version": 1, "objects": [{ "vertices": [[0, 0, 0, 0]], "edges": [[0, 1]] }] }"#;
+        assert!(matches!(load(text), Err(Error::Import(ImportError::FileCorrupted))));
+    }
+}
@@ -1,6 +1,18 @@
+mod cli;
+mod animation;
+mod obj;
+mod svg;
 mod save;
+mod dialog;
+mod json;
+mod off;
+mod render;
 mod draw;
+mod slice;
+mod view;
+mod turntable;
 mod angle;
+mod rotor;
 mod error;
 mod button;
 mod import;
@@ -8,8 +20,13 @@ mod window;
 mod cursor;
 mod events;
 mod objects;
+use obj::*;
+use svg::*;
 use draw::*;
-use angle::*;
+use slice::*;
+use view::*;
+use turntable::*;
+use rotor::*;
 use import::*;
 use events::*;
 use button::*;
@@ -31,6 +48,8 @@ use macroquad::prelude::next_frame;
 use macroquad::prelude::draw_circle;
 use macroquad::prelude::mouse_wheel;
 use macroquad::prelude::draw_text_ex;
+use macroquad::prelude::measure_text;
+use macroquad::prelude::get_frame_time;
 use macroquad::prelude::draw_texture;
 use macroquad::prelude::screen_width;
 use macroquad::prelude::load_ttf_font;
@@ -101,10 +120,10 @@ fn clear_selection(object: &mut Object) {
         e.selected = false;
     }
     for f in &mut object.faces {
-        f.2 = false;
+        f.selected = false;
     }
     for c in &mut object.cells {
-        c.3 = false;
+        c.selected = false;
     }
 }
 
@@ -244,14 +263,26 @@ fn update_buttons(windows: &mut WindowGroup) {
     }
 }
 
-#[macroquad::main(window_config)]
-async fn main() {
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    macroquad::Window::from_config(window_config(), editor());
+}
+
+async fn editor() {
     show_mouse(false);
     let mut buttons = vec![
         Button::Click(ClickButton::new( 0.0, 0.0, 20.0, 20.0, Some("sprites/logo.png"),     Align::TopLeft,  ButtonType::Info)),
         Button::Click(ClickButton::new(20.0, 0.0, 20.0, 20.0, Some("sprites/settings.png"), Align::TopLeft,  ButtonType::Settings)),
         Button::Click(ClickButton::new(40.0, 0.0, 20.0, 20.0, Some("sprites/import.png"),   Align::TopLeft,  ButtonType::Import)),
         Button::Click(ClickButton::new(60.0, 0.0, 20.0, 20.0, Some("sprites/save.png"),     Align::TopLeft,  ButtonType::Export)),
+        Button::Click(ClickButton::new(80.0, 0.0, 20.0, 20.0, Some("sprites/obj.png"),      Align::TopLeft,  ButtonType::ObjMenu)),
+        Button::Click(ClickButton::new(100.0, 0.0, 20.0, 20.0, Some("sprites/save_as.png"), Align::TopLeft, ButtonType::ExportSvg)),
+        Button::Click(ClickButton::new(120.0, 0.0, 20.0, 20.0, Some("sprites/projection.png"), Align::TopLeft, ButtonType::Projection)),
+        Button::Click(ClickButton::new(140.0, 0.0, 20.0, 20.0, Some("sprites/view.png"),       Align::TopLeft, ButtonType::ViewMenu)),
+        Button::Click(ClickButton::new(160.0, 0.0, 20.0, 20.0, Some("sprites/turntable.png"),  Align::TopLeft, ButtonType::TurntableMenu)),
     ];
     let mut windows = WindowGroup {
         main:         Window::Main(MainWindow::new(screen_width(), screen_height())),
@@ -274,13 +305,18 @@ async fn main() {
     let mut mouse_state = MouseState::new(mouse_position(), mouse_wheel().1);
     let mut cursor = Cursor::new(mouse_position());
     let mut last_size = (screen_width(), screen_height());
-    let mut angle = Angle::new();
-    let camera = Camera::new(Vec4f::new(0.0, 0.0, 0.0, -5.0));
+    let mut rotor = Rotor::identity();
+    let mut camera = Camera::on_w(CAMERA_DISTANCE);
     let mut axes = Axes::new(100.0, windows.main.config().y - 100.0);
     let mut motion_axes = MotionAxes::new();
     let mut clipboard = Object::empty();
+    let mut slice = SliceView::new();
+    let mut slices = vec![];
+    let mut obj_settings = ObjSettings::new();
+    let mut transition = None;
+    let mut turntable = Turntable::new();
     loop {
-        clear_background(Color::new(0.55294, 0.55294, 0.55294, 1.0));
+        clear_background(BACKGROUND_COLOR);
         mouse_state.scroll_delta = mouse_wheel().1;
         let x_last = mouse_state.pos.0;
         let y_last = mouse_state.pos.1;
@@ -292,7 +328,6 @@ async fn main() {
             last_size = new_size;
         }
         let mut hover = false;
-        let d = dist(Vec4f::new0(), camera.c);
         catch_hover(&mut cursor, &mut buttons, &mut hover, &mut windows);
         catch_mouse_event(
             &mut mouse_state,
@@ -300,26 +335,61 @@ async fn main() {
             &mut objects,
             (x_last, y_last),
             &mut motion_axes,
-            &mut angle,
+            &mut rotor,
+            &axes,
             &mut windows,
+            &mut slice,
+            &slices,
+            &mut camera,
+            &mut obj_settings,
+            &mut transition,
+            &mut turntable,
         );
-        catch_keyboard_event(&mut objects, &mut clipboard, &mut motion_axes);
+        catch_keyboard_event(
+            &mut objects,
+            &mut clipboard,
+            &mut motion_axes,
+            &mut slice,
+            &mut camera,
+            &rotor,
+            &mut transition,
+            &mut turntable,
+        );
+        turntable.advance(get_frame_time(), &mut rotor);
+        if let Some(t) = transition.as_mut() {
+            if !t.advance(get_frame_time(), &mut camera) { transition = None; }
+        }
         update_buttons(&mut windows);
+        slices.clear();
+        if slice.enabled { slices = slice.slices(&objects); }
+        camera.fit(
+            if slice.enabled { &slices } else { &objects }.iter().flat_map(|o| o.vertices.iter()),
+            &rotor,
+        );
         for obj in objects.iter_mut() {
-            obj.calc_vertices(&angle, d, &windows.main);
+            obj.calc_vertices(&rotor, &camera, &windows.main);
+        }
+        if slice.enabled {
+            for obj in slices.iter_mut() {
+                obj.calc_vertices(&rotor, &camera, &windows.main);
+            }
         }
         draw_windows(
             &windows,
-            &objects,
+            if slice.enabled { &slices } else { &objects },
             &buttons,
             &axes,
             &motion_axes,
             &cursor,
+            if slice.enabled { Some((&slice, slice.offset(&objects))) } else { None },
+            &camera,
+            &obj_settings,
+            &turntable,
         );
         if !hover { cursor.reset(); }
         cursor.move_to(mouse_state.pos.0, mouse_state.pos.1);
-        axes.calc(&angle, &windows.main);
-        motion_axes.calc(&angle, &windows.main);
+        axes.calc(&rotor, &camera, &windows.main);
+        motion_axes.calc(&rotor, &camera, &windows.main);
 
         if mouse_state.cursor_transform_timer.elapsed().as_millis() >= CUR_TRANSFORM_TO {
             mouse_state.cursor_transform_timer = Instant::now();
@@ -0,0 +1,319 @@
+use std::io::Read;
+use std::path::Path;
+use crate::error::*;
+use crate::import::open_file;
+use crate::objects::Edge;
+use crate::objects::Face;
+use crate::objects::Vec4f;
+use crate::objects::Object;
+use crate::objects::AXIS_NAMES;
+use crate::button::Button;
+use crate::button::ButtonType;
+use crate::dialog::show_dialog;
+
+const MENU_X:      f32 = 80.0; // the OBJ menu button in the top left row
+const OFFSET_STEP: f32 = 0.5;
+const HEIGHT_STEP: f32 = 0.5;
+
+/// How a 3D Wavefront mesh is lifted into 4D space.
+#[derive(Debug, Copy, Clone)]
+pub enum ObjPlacement {
+    /// Puts the mesh on the hyperplane `axis = offset` (axis index 0..4 is X, Y, Z, W),
+    /// the mesh coordinates fill the three remaining axes in order.
+    Hyperplane { axis: usize, offset: f32 },
+    /// Copies the mesh to `w = -height / 2` and `w = height / 2` and links every
+    /// vertex with its copy, every mesh edge gets a quad side face. A closed mesh
+    /// also gets the cells: both caps and a prism over every mesh face.
+    Prism { height: f32 },
+}
+
+impl Default for ObjPlacement {
+    fn default() -> Self {
+        ObjPlacement::Hyperplane { axis: 3, offset: 0.0 }
+    }
+}
+
+/// Items of the OBJ import menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ObjMenuItem {
+    Placement,
+    Axis,
+    Offset,
+    Height,
+    Import,
+}
+
+impl ObjMenuItem {
+    pub const ALL: [ObjMenuItem; 5] = [
+        ObjMenuItem::Placement,
+        ObjMenuItem::Axis,
+        ObjMenuItem::Offset,
+        ObjMenuItem::Height,
+        ObjMenuItem::Import,
+    ];
+}
+
+/// Placement chosen in the OBJ import menu, used by every OBJ import.
+#[derive(Debug, Copy, Clone)]
+pub struct ObjSettings {
+    pub prism:  bool,
+    pub axis:   usize,
+    pub offset: f32,
+    pub height: f32,
+}
+
+impl ObjSettings {
+    pub fn new() -> Self {
+        ObjSettings {
+            prism:  false,
+            axis:   3,
+            offset: 0.0,
+            height: 2.0,
+        }
+    }
+
+    pub fn placement(&self) -> ObjPlacement {
+        if self.prism {
+            ObjPlacement::Prism { height: self.height }
+        } else {
+            ObjPlacement::Hyperplane { axis: self.axis, offset: self.offset }
+        }
+    }
+
+    /// Handles a click on a menu item, `back` (SHIFT) cycles axes
+    /// backwards and lowers values. `Import` is left to the caller.
+    pub fn click(&mut self, item: ObjMenuItem, back: bool) {
+        let sign = if back { -1.0 } else { 1.0 };
+        match item {
+            ObjMenuItem::Placement => self.prism = !self.prism,
+            ObjMenuItem::Axis => self.axis = (self.axis + if back { AXIS_NAMES.len() - 1 } else { 1 }) % AXIS_NAMES.len(),
+            ObjMenuItem::Offset => self.offset += sign * OFFSET_STEP,
+            ObjMenuItem::Height => self.height = (self.height + sign * HEIGHT_STEP).max(HEIGHT_STEP),
+            ObjMenuItem::Import => {},
+        }
+    }
+
+    pub fn label(&self, item: ObjMenuItem) -> String {
+        match item {
+            ObjMenuItem::Placement if self.prism => "Размещение: призма по W".to_string(),
+            ObjMenuItem::Placement => "Размещение: гиперплоскость".to_string(),
+            ObjMenuItem::Axis => format!("Ось гиперплоскости: {}", AXIS_NAMES[self.axis]),
+            ObjMenuItem::Offset => format!("Смещение: {:.2}", self.offset),
+            ObjMenuItem::Height => format!("Высота призмы: {:.2}", self.height),
+            ObjMenuItem::Import => "Импортировать .obj".to_string(),
+        }
+    }
+}
+
+/// Opens the OBJ import menu under its button in the top left row, or closes it.
+pub fn toggle_obj_menu(buttons: &mut Vec<Button>) {
+    Button::toggle_menu(buttons, MENU_X, ObjMenuItem::ALL.iter().map(|i| ButtonType::Obj(*i)).collect());
+}
+
+fn open_with_explorer() -> Option<String> {
+    show_dialog(|| rfd::FileDialog::new()
+        .add_filter("Wavefront OBJ", &["obj"])
+        .add_filter("Wavefront OBJ", &["*"])
+        .set_directory("/")
+        .set_title("Импортировать")
+        .pick_file())
+}
+
+/// Resolves an OBJ index (1-based, negative means relative to the end).
+fn parse_index(token: &str, count: usize) -> Result<usize, Error> {
+    let raw = token.split('/').next().unwrap_or("");
+    let i: i64 = raw.parse().map_err(|_| Error::Import(ImportError::FileCorrupted))?;
+    let index = if i < 0 { count as i64 + i } else { i - 1 };
+    if index < 0 || index as usize >= count {
+        return Err(Error::Import(ImportError::FileCorrupted));
+    }
+    Ok(index as usize)
+}
+
+/// Reads `v`, `f` and `l` records of a Wavefront OBJ file into a flat (w = 0) object.
+/// Other records are ignored.
+fn read_mesh(text: &str) -> Result<Object, Error> {
+    let mut obj = Object::empty();
+    for line in text.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let mut xyz = [0.0; 3];
+                for c in xyz.iter_mut() {
+                    *c = tokens.next()
+                        .and_then(|t| t.parse().ok())
+                        .ok_or(Error::Import(ImportError::FileCorrupted))?;
+                }
+                obj.vertices.push(Vec4f::new(xyz[0], xyz[1], xyz[2], 0.0));
+            },
+            Some("f") => {
+                let count = obj.vertices.len();
+                let mut indices = vec![];
+                for t in tokens { indices.push(parse_index(t, count)?); }
+                if indices.len() < 3 { return Err(Error::Import(ImportError::FileCorrupted)); }
+                obj.add_face(indices);
+            },
+            Some("l") => {
+                let count = obj.vertices.len();
+                let mut indices = vec![];
+                for t in tokens { indices.push(parse_index(t, count)?); }
+                for pair in indices.windows(2) {
+                    obj.add_edge(pair[0], pair[1]);
+                }
+            },
+            Some("o") => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                if obj.name.is_none() && !name.is_empty() { obj.name = Some(name); }
+            },
+            _ => {},
+        }
+    }
+    Ok(obj)
+}
+
+/// Whether every edge of the mesh borders exactly two of its faces.
+fn is_closed(mesh: &Object) -> bool {
+    let mut uses = vec![0; mesh.edges.len()];
+    for f in &mesh.faces {
+        for e in &f.edges { uses[*e] += 1; }
+    }
+    !mesh.faces.is_empty() && uses.iter().all(|u| *u == 2)
+}
+
+fn place(mesh: Object, placement: ObjPlacement) -> Object {
+    match placement {
+        ObjPlacement::Hyperplane { axis, offset } => {
+            let mut obj = mesh;
+            for v in &mut obj.vertices {
+                let (x, y, z) = v.xyz();
+                *v = match axis {
+                    0 => Vec4f::new(offset, x, y, z),
+                    1 => Vec4f::new(x, offset, y, z),
+                    2 => Vec4f::new(x, y, offset, z),
+                    _ => Vec4f::new(x, y, z, offset),
+                };
+            }
+            obj
+        },
+        ObjPlacement::Prism { height } => {
+            let count = mesh.vertices.len();
+            let e_count = mesh.edges.len();
+            let f_count = mesh.faces.len();
+            let closed = is_closed(&mesh);
+            let mut bottom = mesh.clone();
+            let mut top = mesh;
+            for v in &mut bottom.vertices { v.w = -height / 2.0; }
+            for v in &mut top.vertices { v.w = height / 2.0; }
+            let name = bottom.name.clone();
+            bottom += top;
+            for i in 0..count {
+                bottom.edges.push(Edge::new(i, i + count));
+            }
+            for i in 0..e_count {
+                let (a, b) = (bottom.edges[i].a, bottom.edges[i].b);
+                bottom.faces.push(Face::new(
+                    vec![a, b, b + count, a + count],
+                    vec![i, 2 * e_count + b, i + e_count, 2 * e_count + a],
+                ));
+            }
+            if closed {
+                bottom.add_cell((0..f_count).collect());
+                bottom.add_cell((f_count..2 * f_count).collect());
+                for k in 0..f_count {
+                    let mut faces = vec![k, k + f_count];
+                    faces.extend(bottom.faces[k].edges.iter().map(|e| 2 * f_count + e));
+                    bottom.add_cell(faces);
+                }
+            }
+            bottom.name = name;
+            bottom
+        },
+    }
+}
+
+pub fn read_obj<R: Read>(reader: &mut R, placement: ObjPlacement) -> Result<Object, Error> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(Error::Io)?;
+    Ok(place(read_mesh(&text)?, placement))
+}
+
+pub fn load_obj_from_path<P: AsRef<Path>>(path: P, placement: ObjPlacement) -> Result<Object, Error> {
+    read_obj(&mut open_file(path)?, placement)
+}
+
+/// Asks for an OBJ file and imports it, see `read_obj`.
+pub fn open_obj(placement: ObjPlacement) -> Result<Object, Error> {
+    let path = match open_with_explorer() {
+        Some(p) => p,
+        None => return Err(Error::Cancel),
+    };
+    load_obj_from_path(path, placement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TETRAHEDRON: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3\nf 1 2 4\nf 2 3 4\nf 1 3 4\n";
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+
+    fn read(text: &str, placement: ObjPlacement) -> Object {
+        match read_obj(&mut text.as_bytes(), placement) {
+            Ok(obj) => obj,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn records() {
+        let text = "# square\no Square\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1/1/1 2//1 -2 -1\nl 1 3\nl -1 -2\n";
+        let obj = read(text, ObjPlacement::default());
+        assert_eq!(obj.name.as_deref(), Some("Square"));
+        assert_eq!(obj.vertices.len(), 4);
+        assert_eq!(obj.vertices[2], Vec4f::new(1.0, 1.0, 0.0, 0.0));
+        assert_eq!(obj.faces.len(), 1);
+        assert_eq!(obj.faces[0].vertices, vec![0, 1, 2, 3]);
+        // 4 sides of the square and its diagonal, "l -1 -2" is the side 4 - 3
+        assert_eq!(obj.edges.len(), 5);
+        assert_eq!((obj.edges[4].a, obj.edges[4].b), (0, 2));
+
+        for bad in ["v 0 0\n", "v 0 0 0\nf 1 1\n", "v 0 0 0\nl 1 2\n", "v 0 0 0\nl -2 1\n", "v 0 0 0\nl 0 1\n"] {
+            assert!(read_obj(&mut bad.as_bytes(), ObjPlacement::default()).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn hyperplane() {
+        let obj = read(TRIANGLE, ObjPlacement::Hyperplane { axis: 1, offset: 0.5 });
+        assert_eq!(obj.vertices[1], Vec4f::new(1.0, 0.5, 0.0, 0.0));
+        assert!(obj.cells.is_empty());
+    }
+
+    #[test]
+    fn prism() {
+        let obj = read(TETRAHEDRON, ObjPlacement::Prism { height: 2.0 });
+        assert_eq!(obj.vertices.len(), 8);
+        assert_eq!(obj.edges.len(), 6 * 2 + 4);
+        assert_eq!(obj.faces.len(), 4 * 2 + 6);
+        assert_eq!(obj.cells.len(), 2 + 4);
+        assert_eq!(obj.vertices[5].w, 1.0);
+        for c in &obj.cells[2..] {
+            assert_eq!((c.vertices.len(), c.edges.len(), c.faces.len()), (6, 9, 5));
+        }
+
+        let open = read(TRIANGLE, ObjPlacement::Prism { height: 2.0 });
+        assert_eq!(open.faces.len(), 2 + 3);
+        assert!(open.cells.is_empty());
+    }
+
+    #[test]
+    fn settings() {
+        let mut settings = ObjSettings::new();
+        settings.click(ObjMenuItem::Axis, true);
+        settings.click(ObjMenuItem::Offset, false);
+        assert!(matches!(settings.placement(), ObjPlacement::Hyperplane { axis: 2, offset } if offset == OFFSET_STEP));
+        settings.click(ObjMenuItem::Placement, false);
+        for _ in 0..10 { settings.click(ObjMenuItem::Height, true); }
+        assert!(matches!(settings.placement(), ObjPlacement::Prism { height } if height == HEIGHT_STEP));
+    }
+}
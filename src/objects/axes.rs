@@ -1,7 +1,9 @@
 use crate::window::Window;
-use crate::angle::Angle;
+use crate::rotor::Rotor;
 use super::*;
 
+const AXES_DISTANCE: f32 = 8.0;
+
 #[derive(Debug, Copy, Clone)]
 pub struct Axes {
     pub x: Vec4f,
@@ -21,6 +23,8 @@ pub struct MotionAxes {
     pub grabbed:    bool,
     pub grab_start: Option<Vec4f>,
     pub grab_now:   Option<Vec4f>,
+    pub follow:     bool,  // grabbed without a held button, see `grab_towards`
+    pub moved:      Vec4f, // total motion since `grab_towards`
 }
 
 impl MotionAxes {
@@ -34,20 +38,22 @@ impl MotionAxes {
             grab_now:   None,
             grabbed:    false,
             pos:        None,
+            follow:     false,
+            moved:      Vec4f::new0(),
         }
     }
 
-    pub fn calc(&mut self, a: &Angle, window: &Window) {
+    pub fn calc(&mut self, r: &Rotor, camera: &Camera, window: &Window) {
         if let Some(mut pos) = self.pos {
-            let x = (self.x + pos).calc(a, 5.0, window);
-            let y = (self.y + pos).calc(a, 5.0, window);
-            let z = (self.z + pos).calc(a, 5.0, window);
-            let w = (self.w + pos).calc(a, 5.0, window);
+            let x = (self.x + pos).calc(r, camera, window);
+            let y = (self.y + pos).calc(r, camera, window);
+            let z = (self.z + pos).calc(r, camera, window);
+            let w = (self.w + pos).calc(r, camera, window);
             if let Some(proj) = x.get_proj() { self.x.set_proj(proj); }
             if let Some(proj) = y.get_proj() { self.y.set_proj(proj); }
             if let Some(proj) = z.get_proj() { self.z.set_proj(proj); }
             if let Some(proj) = w.get_proj() { self.w.set_proj(proj); }
-            self.pos = Some(pos.calc(a, 5.0, window));
+            self.pos = Some(pos.calc(r, camera, window));
         }
     }
 
@@ -73,13 +79,50 @@ impl MotionAxes {
         self.grab_start = None;
         self.grab_now   = None;
         self.grabbed    = false;
+        self.follow     = false;
+    }
+
+    /// Grabs the axis under the cursor or, if there is none, the one most
+    /// parallel to the direction to the cursor. The selection then follows
+    /// the cursor until `ungrab`, no mouse button has to be held.
+    pub fn grab_towards(&mut self, x: f32, y: f32) {
+        let origin = match self.pos.and_then(|p| p.get_proj()) {
+            Some(origin) => origin,
+            None => return,
+        };
+        if self.get_closest_axe(x, y).is_none() {
+            let to_cursor = sub2d((x, y), origin);
+            let mut best = None;
+            let mut best_cos = 0.0;
+            for (i, axe) in [self.x, self.y, self.z, self.w].iter().enumerate() {
+                if let Some(proj) = axe.get_proj() {
+                    let axe2d = sub2d(proj, origin);
+                    let len = dist2d((0.0, 0.0), axe2d) * dist2d((0.0, 0.0), to_cursor);
+                    if len < f32::EPSILON { continue; }
+                    let cos = (dot2d(axe2d, to_cursor) / len).abs();
+                    if best.is_none() || cos > best_cos {
+                        best = Some(i);
+                        best_cos = cos;
+                    }
+                }
+            }
+            match best {
+                Some(i) => self.select_axe(i),
+                None => return,
+            }
+        }
+        self.grab_start = self.pos;
+        self.grabbed    = true;
+        self.follow     = true;
+        self.moved      = Vec4f::new0();
     }
 
     fn get_motion_delta_for_axe(
         &mut self,
         axe: Vec4f,
         xy_delta: (f32, f32),
-        a: &Angle,
+        r: &Rotor,
+        camera: &Camera,
         window: &Window
     ) -> Vec4f {
         if let (Some(proj), Some(pos)) = (axe.get_proj(), self.pos) {
@@ -90,9 +133,9 @@ impl MotionAxes {
                 let ratio = proj_delta_to_axe / dist2d((0.0, 0.0), axe2d);
                 let delta = axe * ratio;
                 if let Some(now) = self.grab_now {
-                    self.grab_now = Some((now + delta).calc(a, 5.0, window));
+                    self.grab_now = Some((now + delta).calc(r, camera, window));
                 } else if let Some(start) = self.grab_start {
-                    self.grab_now = Some((start + delta).calc(a, 5.0, window));
+                    self.grab_now = Some((start + delta).calc(r, camera, window));
                 }
                 return delta;
             }
@@ -100,12 +143,12 @@ impl MotionAxes {
         return Vec4f::new0();
     }
 
-    pub fn get_motion_delta(&mut self, xy_delta: (f32, f32), a: &Angle, window: &Window) -> Vec4f {
+    pub fn get_motion_delta(&mut self, xy_delta: (f32, f32), r: &Rotor, camera: &Camera, window: &Window) -> Vec4f {
         if !self.any_axe_selected() { return Vec4f::new0(); }
-        if self.x.selected { return self.get_motion_delta_for_axe(self.x, xy_delta, a, window); }
-        if self.y.selected { return self.get_motion_delta_for_axe(self.y, xy_delta, a, window); }
-        if self.z.selected { return self.get_motion_delta_for_axe(self.z, xy_delta, a, window); }
-        if self.w.selected { return self.get_motion_delta_for_axe(self.w, xy_delta, a, window); }
+        if self.x.selected { return self.get_motion_delta_for_axe(self.x, xy_delta, r, camera, window); }
+        if self.y.selected { return self.get_motion_delta_for_axe(self.y, xy_delta, r, camera, window); }
+        if self.z.selected { return self.get_motion_delta_for_axe(self.z, xy_delta, r, camera, window); }
+        if self.w.selected { return self.get_motion_delta_for_axe(self.w, xy_delta, r, camera, window); }
         return Vec4f::new0();
     }
 
@@ -170,10 +213,14 @@ impl Axes {
         }
     }
 
-    pub fn calc(&mut self, a: &Angle, window: &Window) {
-        self.x.calc(a, 8.0, window);
-        self.y.calc(a, 8.0, window);
-        self.z.calc(a, 8.0, window);
-        self.w.calc(a, 8.0, window);
+    /// The axes are seen from `AXES_DISTANCE` in the camera direction,
+    /// so they turn with the camera but do not zoom or pan.
+    pub fn calc(&mut self, r: &Rotor, camera: &Camera, window: &Window) {
+        let mut camera = camera.gizmo(AXES_DISTANCE);
+        camera.fit([self.x, self.y, self.z, self.w].iter(), r);
+        self.x.calc(r, &camera, window);
+        self.y.calc(r, &camera, window);
+        self.z.calc(r, &camera, window);
+        self.w.calc(r, &camera, window);
     }
 }
\ No newline at end of file
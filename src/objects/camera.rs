@@ -1,15 +1,271 @@
 use super::Vec4f;
+use super::vector::SCALE;
+use super::vector::UNIT_AXES;
+use crate::rotor::Rotor;
 
+pub const CAMERA_DISTANCE: f32 = 5.0; // default distance to the origin
+const MIN_DISTANCE: f32 = 1.0; // closest zoom, from the origin along the look direction
+const SCHLEGEL_GAP: f32 = 0.5; // eye height above the facing cell, relative to its distance
+
+/// Vector `v` with `v · x = det[x, a, b, c]`, orthogonal to `a`, `b` and `c`.
+fn cross4(a: Vec4f, b: Vec4f, c: Vec4f) -> Vec4f {
+    let det3 = |a: (f32, f32, f32), b: (f32, f32, f32), c: (f32, f32, f32)| {
+        a.0 * (b.1 * c.2 - b.2 * c.1) - a.1 * (b.0 * c.2 - b.2 * c.0) + a.2 * (b.0 * c.1 - b.1 * c.0)
+    };
+    Vec4f::new(
+         det3(a.yzw(), b.yzw(), c.yzw()),
+        -det3(a.xzw(), b.xzw(), c.xzw()),
+         det3(a.xyw(), b.xyw(), c.xyw()),
+        -det3(a.xyz(), b.xyz(), c.xyz()),
+    )
+}
+
+/// How a stage of `Camera` flattens a dimension: 4D to 3D or 3D to 2D.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Projection {
+    Orthographic,
+    Perspective,
+    Stereographic, // from the sphere around the target, the pole faces the camera
+    Schlegel,      // perspective from just above the cell (face in 3D) facing the camera
+}
+
+impl Projection {
+    pub fn next(self) -> Self {
+        match self {
+            Projection::Orthographic  => Projection::Perspective,
+            Projection::Perspective   => Projection::Stereographic,
+            Projection::Stereographic => Projection::Schlegel,
+            Projection::Schlegel      => Projection::Orthographic,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Projection::Orthographic  => "ортогональная",
+            Projection::Perspective   => "перспективная",
+            Projection::Stereographic => "стереографическая",
+            Projection::Schlegel      => "диаграмма Шлегеля",
+        }
+    }
+
+    /// Factor for the coordinates across the view of a point `toward` the eye
+    /// from the target, `lateral` away from the view line. The eye is `d` away
+    /// from the target, `s` is the focal length, `support` the largest `toward`
+    /// of the scene. Every mode keeps the scale of `Perspective` at the target.
+    fn factor(self, lateral: f32, toward: f32, d: f32, s: f32, support: f32) -> f32 {
+        match self {
+            Projection::Orthographic  => s / d,
+            Projection::Perspective   => s / (d - toward),
+            Projection::Stereographic => {
+                let r = (lateral * lateral + toward * toward).sqrt();
+                s / d * r / (r - toward).max(f32::EPSILON)
+            }
+            Projection::Schlegel => {
+                if support <= 0.0 { return s / (d - toward); }
+                let gap = SCHLEGEL_GAP * support;
+                s / d * gap / (support + gap - toward)
+            }
+        }
+    }
+}
+
+/// 4D camera. Points are projected onto the 3D space orthogonal to `n` (its
+/// axes are "right", `up` and `over`) by `projection`, then that space is seen
+/// along `over` from the same distance and flattened by `projection3d`.
+#[derive(Debug, Copy, Clone)]
 pub struct Camera {
-    pub c: Vec4f, // center
-    pub n: Vec4f, // normal (direction)
+    pub c:            Vec4f, // center
+    pub n:            Vec4f, // normal (direction)
+    pub up:           Vec4f, // screen Y
+    pub over:         Vec4f, // depth of the 3D view
+    pub fov:          f32,   // field of view of both stages, radians
+    pub projection:   Projection,
+    pub projection3d: Projection,
+    pub support:      (f32, f32), // largest distance toward the eye in 4D and 3D, see `fit`
 }
 
 impl Camera {
+    /// Perspective camera at `c` looking at the origin.
     pub fn new(c: Vec4f) -> Self {
         Camera {
-            c: c.clone(),
-            n: Vec4f::new(0.0, 0.0, 0.0, 1.0),
+            c:            c.clone(),
+            n:            c.norm() * -1.0,
+            up:           UNIT_AXES[1],
+            over:         UNIT_AXES[2],
+            fov:          std::f32::consts::PI / 2.0,
+            projection:   Projection::Perspective,
+            projection3d: Projection::Perspective,
+            support:      (0.0, 0.0),
+        }
+    }
+
+    /// Camera on the W axis at distance `d` looking at the origin, the default view.
+    pub fn on_w(d: f32) -> Self {
+        Camera::new(UNIT_AXES[3] * d)
+    }
+
+    /// Orthonormal right, up, over and forward directions.
+    pub fn basis(&self) -> [Vec4f; 4] {
+        let forward = self.n.norm();
+        let up = (self.up - forward * self.up.dot(forward)).norm();
+        let over = self.over - forward * self.over.dot(forward);
+        let over = (over - up * over.dot(up)).norm();
+        let right = cross4(up, over, forward) * -1.0;
+        [right, up, over, forward]
+    }
+
+    /// Distance from the camera to the origin along the look direction.
+    pub fn distance(&self) -> f32 {
+        -self.c.dot(self.n.norm())
+    }
+
+    fn focal(&self) -> f32 {
+        1.0 / (self.fov / 2.0).tan()
+    }
+
+    /// Point on the view line closest to the origin.
+    fn target(&self) -> Vec4f {
+        self.c + self.n.norm() * self.distance()
+    }
+
+    /// Coordinates of `p` after the 4D stage, the last one points toward the eye,
+    /// and the distance the 3D stage looks from. In perspective it is the depth
+    /// of the point itself, as the editor always did, otherwise the camera distance.
+    fn to_3d(&self, p: Vec4f) -> (f32, f32, f32, f32) {
+        let [right, up, over, forward] = self.basis();
+        let d = self.distance();
+        let t = p - self.target();
+        let toward = -t.dot(forward);
+        let (x, y, z) = (t.dot(right), t.dot(up), t.dot(over));
+        let lateral = (x * x + y * y + z * z).sqrt();
+        let k = self.projection.factor(lateral, toward, d, self.focal(), self.support.0);
+        let depth = if self.projection == Projection::Perspective { d - toward } else { d };
+        (x * k, y * k, z * k, depth)
+    }
+
+    /// Screen position of `p` on a viewport of the given size (width, height).
+    pub fn project(&self, p: Vec4f, size: (f32, f32)) -> (f32, f32) {
+        let (x, y, z, d) = self.to_3d(p);
+        let lateral = (x * x + y * y).sqrt();
+        let k = self.projection3d.factor(lateral, z, d, self.focal(), self.support.1);
+        let k = k * SCALE / self.focal();
+        (x * k + size.0 / 2.0, y * k + size.1 / 2.0)
+    }
+
+    /// Measures the scene for `Projection::Schlegel`: the eye is put just above
+    /// the farthest points toward it, i.e. the cell (or face) facing the camera.
+    pub fn fit<'a>(&mut self, points: impl Iterator<Item = &'a Vec4f> + Clone, r: &Rotor) {
+        let forward = self.n.norm();
+        let target = self.target();
+        self.support.0 = points.clone()
+            .map(|p| -(r.apply(*p) - target).dot(forward))
+            .fold(0.0, f32::max);
+        self.support.1 = points
+            .map(|p| self.to_3d(r.apply(*p)).2)
+            .fold(0.0, f32::max);
+    }
+
+    /// Turns the camera to look against `dir` at the origin from the same
+    /// distance, e.g. at the cell with outward normal `dir` for a Schlegel
+    /// diagram through it. Drops the pan.
+    pub fn face(&mut self, dir: Vec4f) {
+        let forward = dir.norm() * -1.0;
+        let mut basis = vec![forward];
+        for a in [self.up, self.over].iter().chain(UNIT_AXES.iter()) {
+            let r = basis.iter().fold(*a, |r, b| r - *b * r.dot(*b));
+            if basis.len() < 3 && r.len() > 0.1 { basis.push(r.norm()); }
         }
+        self.c = dir.norm() * self.distance();
+        self.n = forward;
+        self.up = basis[1];
+        self.over = basis[2];
+    }
+
+    /// Turns the camera so that the orthonormal `right` and `up` span the
+    /// screen, looking at the origin from the same distance. Drops the pan.
+    pub fn orient(&mut self, right: Vec4f, up: Vec4f) {
+        let axes = [
+            Vec4f::new(1.0, 0.0, 0.0, 0.0),
+            Vec4f::new(0.0, 1.0, 0.0, 0.0),
+            Vec4f::new(0.0, 0.0, 1.0, 0.0),
+            Vec4f::new(0.0, 0.0, 0.0, 1.0),
+        ];
+        let mut basis = vec![right, up];
+        for a in axes.iter() {
+            let r = basis.iter().fold(*a, |r, b| r - *b * r.dot(*b));
+            if basis.len() < 4 && r.len() > 0.1 { basis.push(r.norm()); }
+        }
+        let d = self.distance();
+        self.up = up;
+        self.over = basis[2];
+        self.n = basis[3];
+        self.c = basis[3] * -d;
+        if self.basis()[0].dot(right) < 0.0 { self.over = self.over * -1.0; }
+    }
+
+    /// Moves the camera along the look direction, multiplying
+    /// its distance to the origin by `factor`.
+    pub fn zoom(&mut self, factor: f32) {
+        let d = self.distance();
+        let target = (d * factor).max(MIN_DISTANCE);
+        self.c = self.c + self.n.norm() * (d - target);
+    }
+
+    /// Moves the camera sideways so that the origin follows a cursor moved
+    /// by (dx, dy) pixels.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let [right, up, _, _] = self.basis();
+        let d = self.distance();
+        let k = d * d / (self.focal() * SCALE);
+        self.c = self.c - right * (dx * k) - up * (dy * k);
     }
-}
\ No newline at end of file
+
+    /// The same view from `d` away from the origin, without panning.
+    /// Used by gizmos that do not zoom with the scene.
+    pub fn gizmo(&self, d: f32) -> Camera {
+        Camera { c: self.n.norm() * -d, ..*self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::ZOOM_STEP;
+
+    const SIZE: (f32, f32) = (800.0, 600.0);
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-2 && (a.1 - b.1).abs() < 1e-2
+    }
+
+    #[test]
+    fn pan() {
+        let all = [Projection::Orthographic, Projection::Perspective, Projection::Stereographic, Projection::Schlegel];
+        for projection in all.iter() {
+            for projection3d in all.iter() {
+                let mut camera = Camera::on_w(CAMERA_DISTANCE);
+                camera.projection = *projection;
+                camera.projection3d = *projection3d;
+                let origin = camera.project(Vec4f::new0(), SIZE);
+                assert!(close(origin, (SIZE.0 / 2.0, SIZE.1 / 2.0)));
+                camera.pan(30.0, -12.0);
+                let moved = camera.project(Vec4f::new0(), SIZE);
+                assert!(close(moved, (origin.0 + 30.0, origin.1 - 12.0)), "{:?} {:?}", projection, projection3d);
+                assert!((camera.distance() - CAMERA_DISTANCE).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn zoom() {
+        let mut camera = Camera::on_w(CAMERA_DISTANCE);
+        let p = Vec4f::new(1.0, 1.0, 0.0, 0.0);
+        let far = camera.project(p, SIZE);
+        camera.zoom(1.0 / ZOOM_STEP);
+        assert!((camera.distance() - CAMERA_DISTANCE / ZOOM_STEP).abs() < 1e-4);
+        let near = camera.project(p, SIZE);
+        assert!(near.0 > far.0 && near.1 > far.1);
+        camera.zoom(0.0);
+        assert!((camera.distance() - MIN_DISTANCE).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,183 @@
+use super::Edge;
+use super::Face;
+use super::Vec4f;
+use super::Object;
+use super::UNIT_AXES;
+
+const EPS: f32 = 1e-4;
+
+/// Indices of the copies made by `Object::copy_selected`, `None` where not copied.
+struct Copies {
+    vertices: Vec<Option<usize>>,
+    edges:    Vec<Option<usize>>,
+    faces:    Vec<Option<usize>>,
+    cells:    Vec<usize>,
+}
+
+/// Unit normal of the hyperplane through the points, if they span exactly 3 dimensions.
+fn hyperplane_normal(points: &Vec<Vec4f>) -> Option<Vec4f> {
+    let origin = *points.first()?;
+    let scale = points.iter().map(|p| (*p - origin).len()).fold(0.0, f32::max);
+    let mut basis: Vec<Vec4f> = vec![];
+    for p in points {
+        let mut d = *p - origin;
+        for b in &basis { d = d - *b * d.dot(*b); }
+        if d.len() > EPS * scale.max(1.0) { basis.push(d.norm()); }
+        if basis.len() > 3 { return None; }
+    }
+    if basis.len() != 3 { return None; }
+    UNIT_AXES.iter()
+        .map(|a| basis.iter().fold(*a, |r, b| r - *b * r.dot(*b)))
+        .fold(None, |best: Option<Vec4f>, r| match best {
+            Some(best) if best.len() >= r.len() => Some(best),
+            _ => Some(r),
+        })
+        .map(|n| n.norm())
+}
+
+impl Object {
+    /// Copies the selected vertices, moved by `offset`, with the edges, faces
+    /// and cells spanned by them. The copies become the new selection.
+    fn copy_selected(&mut self, offset: Vec4f) -> Copies {
+        let mut copies = Copies {
+            vertices: vec![None; self.vertices.len()],
+            edges:    vec![None; self.edges.len()],
+            faces:    vec![None; self.faces.len()],
+            cells:    vec![],
+        };
+        for i in self.get_selected_vertices() {
+            self.vertices.push(self.vertices[i] + offset);
+            copies.vertices[i] = Some(self.vertices.len() - 1);
+        }
+        for i in 0..copies.edges.len() {
+            let (a, b) = (self.edges[i].a, self.edges[i].b);
+            if let (Some(a), Some(b)) = (copies.vertices[a], copies.vertices[b]) {
+                self.edges.push(Edge::new(a, b));
+                copies.edges[i] = Some(self.edges.len() - 1);
+            }
+        }
+        for i in 0..copies.faces.len() {
+            let f = &self.faces[i];
+            let vertices: Option<Vec<usize>> = f.vertices.iter().map(|v| copies.vertices[*v]).collect();
+            let edges: Option<Vec<usize>> = f.edges.iter().map(|e| copies.edges[*e]).collect();
+            if let (Some(vertices), Some(edges)) = (vertices, edges) {
+                self.faces.push(Face::new(vertices, edges));
+                copies.faces[i] = Some(self.faces.len() - 1);
+            }
+        }
+        for i in 0..self.cells.len() {
+            let faces: Option<Vec<usize>> = self.cells[i].faces.iter().map(|f| copies.faces[*f]).collect();
+            if let Some(faces) = faces { copies.cells.push(self.add_cell(faces)); }
+        }
+        self.clear_selection();
+        for i in copies.vertices.iter().flatten() { self.vertices[*i].selected = true; }
+        for i in copies.edges.iter().flatten() { self.edges[*i].selected = true; }
+        for i in copies.faces.iter().flatten() { self.faces[*i].selected = true; }
+        for i in &copies.cells { self.cells[*i].selected = true; }
+        copies
+    }
+
+    /// Copies the selected vertices with the edges, faces and cells spanned
+    /// by them into this object. The copies become the new selection.
+    pub fn duplicate_selected(&mut self) {
+        self.copy_selected(Vec4f::new0());
+    }
+
+    /// Extrudes the selection by `distance` along `direction`: the copy becomes
+    /// the new selection and every selected vertex, edge and face is joined with
+    /// its copy by an edge, a square and a prism cell. Extruding a whole cube
+    /// with its cell gives a tesseract.
+    pub fn extrude(&mut self, direction: Vec4f, distance: f32) {
+        if direction.len() < EPS { return; }
+        let copies = self.copy_selected(direction.norm() * distance);
+        let mut sides = vec![None; copies.edges.len()];
+        for (i, copy) in copies.vertices.iter().enumerate() {
+            if let Some(copy) = copy { self.add_edge(i, *copy); }
+        }
+        for (i, copy) in copies.edges.iter().enumerate() {
+            if let Some(copy) = copy {
+                let (a, b) = (self.edges[i].a, self.edges[i].b);
+                let (ca, cb) = (self.edges[*copy].a, self.edges[*copy].b);
+                sides[i] = Some(self.add_face(vec![a, b, cb, ca]));
+            }
+        }
+        for (i, copy) in copies.faces.iter().enumerate() {
+            if let Some(copy) = copy {
+                let mut faces = vec![i, *copy];
+                faces.extend(self.faces[i].edges.iter().filter_map(|e| sides[*e]));
+                self.add_cell(faces);
+            }
+        }
+    }
+
+    /// Default extrusion direction: `selection_normal`, else W.
+    pub fn extrude_direction(&self) -> Vec4f {
+        self.selection_normal().unwrap_or(UNIT_AXES[3])
+    }
+
+    /// The average outward normal of the selected cells, else the normal of the
+    /// selected vertices if they lie in a hyperplane.
+    pub fn selection_normal(&self) -> Option<Vec4f> {
+        let n = self.vertices.len().max(1) as f32;
+        let center = self.vertices.iter().fold(Vec4f::new0(), |c, v| c + *v) / n;
+        let outward = |points: Vec<Vec4f>| {
+            let normal = hyperplane_normal(&points)?;
+            let away = points.iter().fold(Vec4f::new0(), |c, v| c + *v) / points.len() as f32 - center;
+            Some(if normal.dot(away) < 0.0 { normal * -1.0 } else { normal })
+        };
+        let mut sum = Vec4f::new0();
+        for c in self.cells.iter().filter(|c| c.vertices.iter().all(|v| self.vertices[*v].selected)) {
+            if let Some(normal) = outward(c.vertices.iter().map(|v| self.vertices[*v]).collect()) {
+                sum += normal;
+            }
+        }
+        if sum.len() < EPS {
+            let selected = self.get_selected_vertices().iter().map(|v| self.vertices[*v]).collect();
+            sum = outward(selected).unwrap_or(Vec4f::new0());
+        }
+        if sum.len() < EPS { None } else { Some(sum.norm()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    /// Cube [-1, 1]³ in the `w = 0` hyperplane with its squares and its cell, all selected.
+    fn cube() -> Object {
+        let mut obj = Object::empty();
+        obj.vertices = (0..8).map(|i| {
+            let c = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            Vec4f::new(c(0), c(1), c(2), 0.0)
+        }).collect();
+        let squares = [[0, 1, 3, 2], [4, 5, 7, 6], [0, 1, 5, 4], [2, 3, 7, 6], [0, 2, 6, 4], [1, 3, 7, 5]];
+        let faces = squares.iter().map(|s| obj.add_face(s.to_vec())).collect();
+        obj.add_cell(faces);
+        for v in &mut obj.vertices { v.selected = true; }
+        obj
+    }
+
+    #[test]
+    fn tesseract() {
+        let mut obj = cube();
+        let direction = obj.extrude_direction();
+        assert!((direction.w.abs() - 1.0).abs() < EPS);
+        obj.extrude(direction, 2.0);
+        assert_eq!(counts(&obj), (16, 32, 24, 8));
+        assert!(obj.cells.iter().all(|c| c.faces.len() == 6 && c.vertices.len() == 8));
+        assert_eq!(obj.get_selected_vertices(), (8..16).collect::<Vec<_>>());
+        assert!(obj.vertices[8..].iter().all(|v| (v.w - 2.0 * direction.w).abs() < EPS));
+    }
+
+    #[test]
+    fn duplicate() {
+        let mut obj = cube();
+        obj.duplicate_selected();
+        assert_eq!(counts(&obj), (16, 24, 12, 2));
+        assert_eq!(obj.vertices[..8], obj.vertices[8..]);
+    }
+}
@@ -0,0 +1,29 @@
+/// Polygon bounded by a closed loop of vertices, `edges[i]` joins
+/// `vertices[i]` and `vertices[(i + 1) % n]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face {
+    pub vertices: Vec<usize>,
+    pub edges:    Vec<usize>,
+    pub selected: bool,
+}
+
+impl Face {
+    pub fn new(vertices: Vec<usize>, edges: Vec<usize>) -> Self {
+        Face { vertices, edges, selected: false }
+    }
+}
+
+/// Polyhedron bounded by faces, `vertices` and `edges` are everything they use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub vertices: Vec<usize>,
+    pub edges:    Vec<usize>,
+    pub faces:    Vec<usize>,
+    pub selected: bool,
+}
+
+impl Cell {
+    pub fn new(vertices: Vec<usize>, edges: Vec<usize>, faces: Vec<usize>) -> Self {
+        Cell { vertices, edges, faces, selected: false }
+    }
+}
@@ -0,0 +1,106 @@
+use super::Vec4f;
+use super::Object;
+use super::hull::convex_hull;
+use std::collections::HashMap;
+
+impl Object {
+    /// Adds a face bounded by the selected edges. They should form a closed
+    /// loop or a path, which is then closed with a new edge. Without selected
+    /// edges the selected vertices are used if they lie in one plane.
+    /// Returns the index of the new (or the already existing) face.
+    pub fn fill_face(&mut self) -> Option<usize> {
+        let selected: Vec<usize> = (0..self.edges.len()).filter(|e| self.edges[*e].selected).collect();
+        let vertices = if selected.is_empty() {
+            self.coplanar_loop()?
+        } else {
+            self.edge_loop(&selected)?
+        };
+        if vertices.len() < 3 { return None; }
+        let face = match self.find_face(&vertices) {
+            Some(face) => face,
+            None => self.add_face(vertices),
+        };
+        for e in self.faces[face].edges.clone() { self.select_edge(e); }
+        self.faces[face].selected = true;
+        Some(face)
+    }
+
+    /// Adds a cell bounded by the selected faces (faces with all vertices
+    /// selected count too). Every edge of them should be shared by exactly
+    /// two of the faces and they should be connected.
+    /// Returns the index of the new (or the already existing) cell.
+    pub fn fill_cell(&mut self) -> Option<usize> {
+        let faces: Vec<usize> = (0..self.faces.len())
+            .filter(|f| {
+                let face = &self.faces[*f];
+                face.selected || face.vertices.iter().all(|v| self.vertices[*v].selected)
+            })
+            .collect();
+        if faces.len() < 4 { return None; }
+
+        let mut sharing: HashMap<usize, Vec<usize>> = HashMap::new();
+        for f in &faces {
+            let vertices = self.faces[*f].vertices.clone();
+            for i in 0..vertices.len() {
+                let e = self.add_edge(vertices[i], vertices[(i + 1) % vertices.len()]);
+                sharing.entry(e).or_insert_with(Vec::new).push(*f);
+            }
+        }
+        if sharing.values().any(|s| s.len() != 2) { return None; }
+        let mut reached = vec![faces[0]];
+        let mut i = 0;
+        while i < reached.len() {
+            let face = reached[i];
+            for s in sharing.values().filter(|s| s.contains(&face)) {
+                for f in s {
+                    if !reached.contains(f) { reached.push(*f); }
+                }
+            }
+            i += 1;
+        }
+        if reached.len() != faces.len() { return None; }
+
+        let cell = self.cells.iter().position(|c| {
+            c.faces.len() == faces.len() && faces.iter().all(|f| c.faces.contains(f))
+        });
+        let cell = match cell {
+            Some(cell) => cell,
+            None => self.add_cell(faces),
+        };
+        self.cells[cell].selected = true;
+        Some(cell)
+    }
+
+    /// Orders the vertices of the given edges into a loop. The edges should
+    /// form a single closed loop or a single path with two open ends.
+    fn edge_loop(&self, edges: &Vec<usize>) -> Option<Vec<usize>> {
+        let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for e in edges {
+            let (a, b) = (self.edges[*e].a, self.edges[*e].b);
+            neighbors.entry(a).or_insert_with(Vec::new).push(b);
+            neighbors.entry(b).or_insert_with(Vec::new).push(a);
+        }
+        if neighbors.values().any(|n| n.len() > 2) { return None; }
+        let ends: Vec<usize> = neighbors.keys().filter(|v| neighbors[v].len() == 1).copied().collect();
+        if ends.len() != 0 && ends.len() != 2 { return None; }
+        let start = match ends.first() {
+            Some(end) => *end,
+            None => *neighbors.keys().min()?,
+        };
+        let mut res = vec![start];
+        while let Some(next) = neighbors[&res[res.len() - 1]].iter().find(|v| !res.contains(v)) {
+            res.push(*next);
+        }
+        if res.len() != neighbors.len() { return None; }
+        Some(res)
+    }
+
+    /// Selected vertices ordered around their convex hull, if they lie in one plane.
+    fn coplanar_loop(&self) -> Option<Vec<usize>> {
+        let indices = self.get_selected_vertices();
+        let points: Vec<Vec4f> = indices.iter().map(|i| self.vertices[*i]).collect();
+        let hull = convex_hull(&points, &mut |_, _| {});
+        if hull.faces.len() != 1 || hull.edges.len() != indices.len() { return None; }
+        Some(hull.faces[0].iter().map(|i| indices[*i]).collect())
+    }
+}
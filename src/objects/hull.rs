@@ -0,0 +1,446 @@
+use super::Vec4f;
+use super::Object;
+use std::collections::HashMap;
+
+type Point = [f64; 4];
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3]
+}
+
+fn scaled(a: Point, k: f64) -> Point {
+    [a[0] * k, a[1] * k, a[2] * k, a[3] * k]
+}
+
+fn det(m: &Vec<Vec<f64>>) -> f64 {
+    match m.len() {
+        0 => 1.0,
+        1 => m[0][0],
+        n => (0..n).map(|c| {
+            let minor = m[1..].iter().map(|row| {
+                row.iter().enumerate().filter(|(j, _)| *j != c).map(|(_, x)| *x).collect()
+            }).collect();
+            let sign = if c % 2 == 0 { 1.0 } else { -1.0 };
+            sign * m[0][c] * det(&minor)
+        }).sum(),
+    }
+}
+
+/// Vector orthogonal to `k - 1` vectors of a `k`-dimensional space
+/// (cross product generalised through cofactors).
+fn orthogonal(vectors: &Vec<Point>, k: usize) -> Point {
+    let mut n = [0.0; 4];
+    for i in 0..k {
+        let minor = vectors.iter().map(|v| {
+            (0..k).filter(|j| *j != i).map(|j| v[j]).collect()
+        }).collect();
+        n[i] = if i % 2 == 0 { det(&minor) } else { -det(&minor) };
+    }
+    n
+}
+
+/// Affine span of the points: origin, orthonormal basis and the points
+/// picked to build it, which form a non-degenerate simplex.
+struct Span {
+    origin:  Point,
+    basis:   Vec<Point>,
+    simplex: Vec<usize>,
+}
+
+impl Span {
+    fn new(points: &[Point], indices: &[usize], eps: f64) -> Self {
+        let origin = points[indices[0]];
+        let mut span = Span { origin, basis: vec![], simplex: vec![indices[0]] };
+        while span.basis.len() < 4 {
+            let mut best = (0.0, None);
+            for i in indices {
+                let r = span.residual(points[*i]);
+                let d = dot(r, r).sqrt();
+                if d > best.0 { best = (d, Some((*i, r))); }
+            }
+            match best {
+                (d, Some((i, r))) if d > eps => {
+                    span.basis.push(scaled(r, 1.0 / d));
+                    span.simplex.push(i);
+                },
+                _ => break,
+            }
+        }
+        span
+    }
+
+    /// Part of `p - origin` not covered by the basis.
+    fn residual(&self, p: Point) -> Point {
+        let mut r = sub(p, self.origin);
+        for b in &self.basis { r = sub(r, scaled(*b, dot(r, *b))); }
+        r
+    }
+
+    fn local(&self, p: Point) -> Point {
+        let mut c = [0.0; 4];
+        for (i, b) in self.basis.iter().enumerate() { c[i] = dot(sub(p, self.origin), *b); }
+        c
+    }
+}
+
+/// Simplicial facet of a quickhull, `normal` points outwards.
+struct Facet {
+    vertices: Vec<usize>,
+    normal:   Point,
+    offset:   f64,
+    outside:  Vec<usize>,
+    alive:    bool,
+}
+
+impl Facet {
+    fn new(coords: &[Point], vertices: Vec<usize>, k: usize, inside: Point) -> Self {
+        let p0 = coords[vertices[0]];
+        let vectors = vertices[1..].iter().map(|v| sub(coords[*v], p0)).collect();
+        let mut normal = orthogonal(&vectors, k);
+        normal = scaled(normal, 1.0 / dot(normal, normal).sqrt());
+        let mut offset = dot(normal, p0);
+        if dot(normal, inside) > offset {
+            normal = scaled(normal, -1.0);
+            offset = -offset;
+        }
+        Facet { vertices, normal, offset, outside: vec![], alive: true }
+    }
+
+    fn distance(&self, p: Point) -> f64 {
+        dot(self.normal, p) - self.offset
+    }
+
+    fn ridges(&self) -> Vec<Vec<usize>> {
+        (0..self.vertices.len()).map(|i| {
+            let mut r: Vec<usize> = self.vertices.iter().enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, v)| *v)
+                .collect();
+            r.sort();
+            r
+        }).collect()
+    }
+}
+
+/// Quickhull of `k`-dimensional points (k = 3 or 4) starting from a non-degenerate
+/// simplex. Returns the facets grouped by their hyperplanes as vertex sets.
+fn quickhull(
+    coords:   &[Point],
+    k:        usize,
+    simplex:  &[usize],
+    eps:      f64,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Vec<Vec<usize>> {
+    let mut inside = [0.0; 4];
+    for i in simplex {
+        for j in 0..4 { inside[j] += coords[*i][j] / simplex.len() as f64; }
+    }
+    let mut facets: Vec<Facet> = vec![];
+    let mut ridges: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+    let add = |facets: &mut Vec<Facet>, ridges: &mut HashMap<Vec<usize>, Vec<usize>>, vertices: Vec<usize>| {
+        let facet = Facet::new(coords, vertices, k, inside);
+        for r in facet.ridges() { ridges.entry(r).or_insert_with(Vec::new).push(facets.len()); }
+        facets.push(facet);
+        facets.len() - 1
+    };
+    for i in 0..simplex.len() {
+        let vertices = simplex.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, v)| *v).collect();
+        add(&mut facets, &mut ridges, vertices);
+    }
+    let total = coords.len();
+    let mut pending = 0;
+    for p in 0..total {
+        if simplex.contains(&p) { continue; }
+        if let Some(f) = facets.iter_mut().find(|f| f.distance(coords[p]) > eps) {
+            f.outside.push(p);
+            pending += 1;
+        }
+    }
+    let mut current = 0;
+    while current < facets.len() {
+        if !facets[current].alive || facets[current].outside.is_empty() {
+            current += 1;
+            continue;
+        }
+        let outside = &facets[current].outside;
+        let apex = *outside.iter()
+            .max_by(|a, b| {
+                let (da, db) = (facets[current].distance(coords[**a]), facets[current].distance(coords[**b]));
+                da.partial_cmp(&db).unwrap()
+            })
+            .unwrap();
+        let mut visible = vec![current];
+        let mut stack = vec![current];
+        while let Some(f) = stack.pop() {
+            for r in facets[f].ridges() {
+                for n in &ridges[&r] {
+                    if visible.contains(n) || facets[*n].distance(coords[apex]) <= eps { continue; }
+                    visible.push(*n);
+                    stack.push(*n);
+                }
+            }
+        }
+        let mut horizon = vec![];
+        let mut orphans = vec![];
+        for f in &visible {
+            for r in facets[*f].ridges() {
+                if ridges[&r].iter().all(|n| visible.contains(n)) { continue; }
+                horizon.push(r);
+            }
+            orphans.append(&mut facets[*f].outside);
+            facets[*f].alive = false;
+            for r in facets[*f].ridges() {
+                if let Some(list) = ridges.get_mut(&r) { list.retain(|n| n != f); }
+            }
+        }
+        let first_new = facets.len();
+        for mut r in horizon {
+            r.push(apex);
+            add(&mut facets, &mut ridges, r);
+        }
+        pending -= orphans.len();
+        for p in orphans {
+            if p == apex { continue; }
+            if let Some(f) = facets[first_new..].iter_mut().find(|f| f.distance(coords[p]) > eps) {
+                f.outside.push(p);
+                pending += 1;
+            }
+        }
+        progress(total - pending, total);
+    }
+    progress(total, total);
+
+    // merge neighbouring facets lying in the same hyperplane
+    let mut group: Vec<usize> = (0..facets.len()).collect();
+    fn root(group: &mut Vec<usize>, i: usize) -> usize {
+        if group[i] != i { let r = root(group, group[i]); group[i] = r; }
+        group[i]
+    }
+    for list in ridges.values() {
+        if list.len() != 2 { continue; }
+        let (a, b) = (&facets[list[0]], &facets[list[1]]);
+        if dot(a.normal, b.normal) > 1.0 - 1e-9 && (a.offset - b.offset).abs() < eps {
+            let (ra, rb) = (root(&mut group, list[0]), root(&mut group, list[1]));
+            group[ra] = rb;
+        }
+    }
+    let mut merged: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, f) in facets.iter().enumerate() {
+        if !f.alive { continue; }
+        let vertices = merged.entry(root(&mut group, i)).or_insert_with(Vec::new);
+        for v in &f.vertices { if !vertices.contains(v) { vertices.push(*v); } }
+    }
+    let mut res: Vec<Vec<usize>> = merged.into_iter().map(|(_, mut v)| { v.sort(); v }).collect();
+    res.sort();
+    res
+}
+
+/// Hull elements referring to the indices of the input points.
+#[derive(Debug, Clone, Default)]
+pub struct Hull {
+    pub edges: Vec<(usize, usize)>,
+    pub faces: Vec<Vec<usize>>, // vertex loops
+    pub cells: Vec<Vec<usize>>, // indices of faces
+    known_edges: HashMap<(usize, usize), usize>,
+    known_faces: HashMap<Vec<usize>, usize>,
+}
+
+impl Hull {
+    fn add_edge(&mut self, a: usize, b: usize) {
+        let key = (a.min(b), a.max(b));
+        if self.known_edges.contains_key(&key) { return; }
+        self.known_edges.insert(key, self.edges.len());
+        self.edges.push(key);
+    }
+
+    fn add_face(&mut self, polygon: Vec<usize>) -> Option<usize> {
+        if polygon.len() < 3 { return None; }
+        let mut key = polygon.clone();
+        key.sort();
+        if let Some(f) = self.known_faces.get(&key) { return Some(*f); }
+        for i in 0..polygon.len() { self.add_edge(polygon[i], polygon[(i + 1) % polygon.len()]); }
+        self.known_faces.insert(key, self.faces.len());
+        self.faces.push(polygon);
+        Some(self.faces.len() - 1)
+    }
+
+    /// Adds a 3D cell from the points of its boundary.
+    fn add_cell(&mut self, points: &[Point], indices: &[usize], eps: f64) {
+        let span = Span::new(points, indices, eps);
+        if span.basis.len() != 3 { return; }
+        let mut faces = vec![];
+        for facet in facets(points, indices, &span, eps, &mut |_, _| {}) {
+            if let Some(f) = self.add_face(polygon(points, &facet, eps)) { faces.push(f); }
+        }
+        self.cells.push(faces);
+    }
+}
+
+/// Facets of the hull of points spanning a 3D or 4D space, as global indices.
+fn facets(
+    points:   &[Point],
+    indices:  &[usize],
+    span:     &Span,
+    eps:      f64,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Vec<Vec<usize>> {
+    let coords: Vec<Point> = indices.iter().map(|i| span.local(points[*i])).collect();
+    let simplex: Vec<usize> = span.simplex.iter()
+        .map(|s| indices.iter().position(|i| i == s).unwrap())
+        .collect();
+    quickhull(&coords, span.basis.len(), &simplex, eps, progress)
+        .into_iter()
+        .map(|f| f.into_iter().map(|i| indices[i]).collect())
+        .collect()
+}
+
+/// Corners of a flat convex polygon in loop order (monotone chain),
+/// points on its sides or inside are dropped.
+fn polygon(points: &[Point], indices: &[usize], eps: f64) -> Vec<usize> {
+    let span = Span::new(points, indices, eps);
+    if span.basis.len() != 2 { return vec![]; }
+    let mut sorted: Vec<(f64, f64, usize)> = indices.iter()
+        .map(|i| { let c = span.local(points[*i]); (c[0], c[1], *i) })
+        .collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cross = |o: (f64, f64, usize), a: (f64, f64, usize), b: (f64, f64, usize)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut chain: Vec<(f64, f64, usize)> = vec![];
+    for pass in 0..2 {
+        let start = chain.len();
+        let iter: Box<dyn Iterator<Item = &(f64, f64, usize)>> = if pass == 0 {
+            Box::new(sorted.iter())
+        } else {
+            Box::new(sorted.iter().rev())
+        };
+        for p in iter {
+            while chain.len() >= start + 2
+                && cross(chain[chain.len() - 2], chain[chain.len() - 1], *p) <= eps {
+                chain.pop();
+            }
+            chain.push(*p);
+        }
+        chain.pop();
+    }
+    chain.into_iter().map(|p| p.2).collect()
+}
+
+/// Convex hull of the points: edges, polygon faces and polyhedral cells.
+/// Degenerate inputs give the hull in their own span, so coplanar points
+/// give one polygon and cospatial points one cell. `progress` gets the number
+/// of processed points and the total count while a 3D or 4D hull is built.
+pub fn convex_hull(points: &[Vec4f], progress: &mut dyn FnMut(usize, usize)) -> Hull {
+    let mut hull = Hull::default();
+    if points.is_empty() { return hull; }
+    let points: Vec<Point> = points.iter().map(|v| [v.x as f64, v.y as f64, v.z as f64, v.w as f64]).collect();
+    let scale = points.iter()
+        .map(|p| dot(sub(*p, points[0]), sub(*p, points[0])).sqrt())
+        .fold(1.0, f64::max);
+    let eps = scale * 1e-5;
+    let indices: Vec<usize> = (0..points.len()).collect();
+    let span = Span::new(&points, &indices, eps);
+    match span.basis.len() {
+        0 => {},
+        1 => {
+            let along = |i: &usize| span.local(points[*i])[0];
+            let min = indices.iter().min_by(|a, b| along(a).partial_cmp(&along(b)).unwrap()).unwrap();
+            let max = indices.iter().max_by(|a, b| along(a).partial_cmp(&along(b)).unwrap()).unwrap();
+            hull.add_edge(*min, *max);
+        },
+        2 => { hull.add_face(polygon(&points, &indices, eps)); },
+        3 => hull.add_cell(&points, &indices, eps),
+        _ => {
+            for cell in facets(&points, &indices, &span, eps, progress) {
+                hull.add_cell(&points, &cell, eps);
+            }
+        },
+    }
+    hull
+}
+
+impl Object {
+    /// Adds the convex hull of the given vertices to the object, see `convex_hull`.
+    /// Existing vertices, edges and faces are reused, points inside the hull
+    /// stay unconnected.
+    pub fn fill_hull(&mut self, vertices: &[usize], progress: &mut dyn FnMut(usize, usize)) {
+        let points: Vec<Vec4f> = vertices.iter().map(|i| self.vertices[*i]).collect();
+        let hull = convex_hull(&points, progress);
+        for (a, b) in &hull.edges { self.add_edge(vertices[*a], vertices[*b]); }
+        let faces: Vec<usize> = hull.faces.iter().map(|f| {
+            let polygon = f.iter().map(|i| vertices[*i]).collect();
+            match self.find_face(&polygon) {
+                Some(face) => face,
+                None => self.add_face(polygon),
+            }
+        }).collect();
+        for c in &hull.cells {
+            self.add_cell(c.iter().map(|f| faces[*f]).collect());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    fn filled(vertices: Vec<Vec4f>) -> Object {
+        let mut obj = Object::empty();
+        obj.vertices = vertices;
+        let all: Vec<usize> = (0..obj.vertices.len()).collect();
+        obj.fill_hull(&all, &mut |_, _| {});
+        obj
+    }
+
+    /// Vertices of the cube [-1, 1]³ in the `w = 0` hyperplane.
+    fn cube() -> Vec<Vec4f> {
+        (0..8).map(|i| {
+            let c = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            Vec4f::new(c(0), c(1), c(2), 0.0)
+        }).collect()
+    }
+
+    #[test]
+    fn cell5() {
+        let obj = filled(Object::cell5().vertices);
+        assert_eq!(counts(&obj), (5, 10, 10, 5));
+    }
+
+    #[test]
+    fn tesseract() {
+        let mut obj = Object::tesseract();
+        // an existing square is reused instead of being added twice
+        let square = obj.add_face(vec![0, 1, 3, 2]);
+        let all: Vec<usize> = (0..obj.vertices.len()).collect();
+        obj.fill_hull(&all, &mut |_, _| {});
+        assert_eq!(counts(&obj), (16, 32, 24, 8));
+        assert_eq!(obj.cells.iter().filter(|c| c.faces.contains(&square)).count(), 2);
+        assert!(obj.cells.iter().all(|c| c.faces.len() == 6 && c.vertices.len() == 8));
+    }
+
+    #[test]
+    fn coplanar() {
+        let mut points: Vec<Vec4f> = cube().into_iter().filter(|v| v.z < 0.0).collect();
+        points.push(Vec4f::new(0.0, 0.0, -1.0, 0.0));
+        let obj = filled(points);
+        assert_eq!(counts(&obj), (5, 4, 1, 0));
+        assert!(!obj.faces[0].vertices.contains(&4));
+    }
+
+    #[test]
+    fn cospatial() {
+        let mut points = cube();
+        points.push(Vec4f::new0());
+        let obj = filled(points);
+        assert_eq!(counts(&obj), (9, 12, 6, 1));
+        assert_eq!(obj.cells[0].faces.len(), 6);
+    }
+}
@@ -1,13 +1,25 @@
 mod axes;
 mod edge;
+mod face;
 mod vector;
 mod object;
+mod polytopes;
+mod spheres;
+mod prisms;
+mod product;
+mod hull;
+mod fill;
+mod extrude;
+mod slice;
+mod view;
 mod camera;
 mod display;
 mod rotation;
 mod comparison;
 pub use edge::*;
+pub use face::*;
 pub use axes::*;
+pub use view::Element;
 pub use camera::*;
 pub use vector::*;
 pub use object::Object;
@@ -16,9 +28,12 @@ pub use std::fmt::Display;
 pub use std::fmt::Formatter;
 use crate::dist_to_edge;
 
-pub const CLICK_TIMEOUT:    u128 = 200;
-pub const CUR_TRANSFORM_TO: u128 = 20;
-pub const MAX_DIST:         f32  = 20.0;
+pub const CLICK_TIMEOUT:      u128  = 200;
+pub const CUR_TRANSFORM_TO:   u128  = 20;
+pub const MAX_DIST:           f32   = 20.0;
+pub const HULL_PROGRESS_MIN:  usize = 500; // points, smaller hulls are built silently
+pub const HULL_PROGRESS_STEP: usize = 5;   // percent between progress reports
+pub const ZOOM_STEP:          f32   = 1.1; // camera distance factor per wheel step
 
 pub fn get_center(objects: &Vec<Object>) -> Option<Vec4f> {
     let mut center = Vec4f::new0();
@@ -1,26 +1,19 @@
 use crate::Edge;
+use super::Face;
+use super::Cell;
 use super::Vec4f;
-use std::f32::consts::PI;
+use super::Camera;
 use std::ops::Add;
 use std::ops::AddAssign;
-use crate::angle::Angle;
+use crate::rotor::Rotor;
 use crate::window::Window;
 
 #[derive(Debug, Clone)]
 pub struct Object {
     pub vertices: Vec<Vec4f>,
     pub edges: Vec<Edge>,
-    pub faces: Vec<(
-        (usize, usize, usize), // vertices
-        (usize, usize, usize), // edges
-        bool,                  // selected
-    )>,
-    pub cells: Vec<(
-        (usize, usize, usize, usize),               // vertices
-        (usize, usize, usize, usize, usize, usize), // edges
-        (usize, usize, usize, usize),               // faces
-        bool,                                       // selected
-    )>,
+    pub faces: Vec<Face>,
+    pub cells: Vec<Cell>,
     pub name: Option<String>,
 }
 
@@ -38,21 +31,21 @@ impl Object {
     pub fn clear_selection(&mut self) {
         for v in &mut self.vertices { v.selected = false; }
         for e in &mut self.edges { e.selected = false; }
-        for f in &mut self.faces { f.2 = false; }
-        for c in &mut self.cells { c.3 = false; }
+        for f in &mut self.faces { f.selected = false; }
+        for c in &mut self.cells { c.selected = false; }
     }
 
     pub fn select(&mut self) -> &mut Self {
         for v in &mut self.vertices { v.selected = true; }
         for e in &mut self.edges { e.selected = true; }
-        for f in &mut self.faces { f.2 = true; }
-        for c in &mut self.cells { c.3 = true; }
+        for f in &mut self.faces { f.selected = true; }
+        for c in &mut self.cells { c.selected = true; }
         self
     }
 
-    pub fn calc_vertices(&mut self, a: &Angle, d: f32,  main: &Window) {
+    pub fn calc_vertices(&mut self, r: &Rotor, camera: &Camera, main: &Window) {
         for (_, v) in self.vertices.iter_mut().enumerate() {
-            v.calc(a, d, main);
+            v.calc(r, camera, main);
         }
     }
 
@@ -121,6 +114,66 @@ impl Object {
         for i in indices.iter().rev() {
             self.edges.remove(*i);
         }
+        let mut f_indices = vec![];
+        for (i, f) in self.faces.iter_mut().enumerate() {
+            if f.vertices.contains(&index) { f_indices.push(i); continue; }
+            for v in &mut f.vertices { if *v > index { *v -= 1; } }
+            for e in &mut f.edges { *e = shifted(*e, &indices); }
+        }
+        for i in f_indices.iter().rev() {
+            self.faces.remove(*i);
+        }
+        self.cells.retain(|c| !c.vertices.contains(&index));
+        for c in &mut self.cells {
+            for v in &mut c.vertices { if *v > index { *v -= 1; } }
+            for e in &mut c.edges { *e = shifted(*e, &indices); }
+            for f in &mut c.faces { *f = shifted(*f, &f_indices); }
+        }
+    }
+
+    pub fn find_edge(&self, a: usize, b: usize) -> Option<usize> {
+        self.edges.iter().position(|e| (e.a == a && e.b == b) || (e.a == b && e.b == a))
+    }
+
+    /// Returns the edge between `a` and `b`, creating it if needed.
+    pub fn add_edge(&mut self, a: usize, b: usize) -> usize {
+        if let Some(i) = self.find_edge(a, b) { return i; }
+        self.edges.push(Edge::new(a, b));
+        self.edges.len() - 1
+    }
+
+    /// Face with the same vertices, in any order.
+    pub fn find_face(&self, vertices: &Vec<usize>) -> Option<usize> {
+        self.faces.iter().position(|f| {
+            f.vertices.len() == vertices.len() && vertices.iter().all(|v| f.vertices.contains(v))
+        })
+    }
+
+    /// Adds a polygon through the given loop of vertices, creating missing edges.
+    pub fn add_face(&mut self, vertices: Vec<usize>) -> usize {
+        let n = vertices.len();
+        let mut edges = vec![];
+        for i in 0..n {
+            edges.push(self.add_edge(vertices[i], vertices[(i + 1) % n]));
+        }
+        self.faces.push(Face::new(vertices, edges));
+        self.faces.len() - 1
+    }
+
+    /// Adds a polyhedron bounded by the given faces.
+    pub fn add_cell(&mut self, faces: Vec<usize>) -> usize {
+        let mut vertices = vec![];
+        let mut edges = vec![];
+        for f in &faces {
+            for v in &self.faces[*f].vertices {
+                if !vertices.contains(v) { vertices.push(*v); }
+            }
+            for e in &self.faces[*f].edges {
+                if !edges.contains(e) { edges.push(*e); }
+            }
+        }
+        self.cells.push(Cell::new(vertices, edges, faces));
+        self.cells.len() - 1
     }
 
     pub fn tesseract() -> Object {
@@ -182,56 +235,11 @@ impl Object {
             name: Some("Tessteract".to_string()),
         }
     }
+}
 
-    pub fn sphere3d() -> Self {
-        let mut vertices = vec![];
-        let rs = 1.5; // start radius
-        let n = 20;
-        for xy in 0..n {
-            let a = 2.0 * PI / n as f32 * xy as f32;
-            let (x, y) = Vec4f::new(a.cos() * rs, a.sin() * rs, 0.0, 0.0).xy();
-            let r = x.abs();
-            for xz in 0..n {
-                let b = 2.0 * PI / n as f32 * xz as f32;
-                let v = Vec4f::new(b.cos() * r, y, b.sin() * r, 0.0);
-                vertices.push(v);
-            }
-        }
-        Self {
-            vertices,
-            edges: vec![],
-            faces: vec![],
-            cells: vec![],
-            name: Some("Sphere 3D".to_string()),
-        }
-    }
-
-    pub fn sphere4d() -> Self {
-        let mut vertices = vec![];
-        let n = 5;
-        for xy in 0..2 * n {
-            let a = PI / n as f32 * xy as f32;
-            let (x, y) = Vec4f::new(a.cos(), a.sin(), 0.0, 0.0).xy();
-            let r = x.abs();
-            for xz in 0..2 * n {
-                let b = PI / n as f32 * xz as f32;
-                let (x, z) = Vec4f::new(b.cos() * r, y, b.sin() * r, 0.0).xz();
-                let r = x.abs();
-                for xw in 0..2 * n {
-                    let g = PI / n as f32 * xw as f32;
-                    let v = Vec4f::new(g.cos() * r, y, z, g.sin() * r);
-                    vertices.push(v);
-                }
-            }
-        }
-        Self {
-            vertices,
-            edges: vec![],
-            faces: vec![],
-            cells: vec![],
-            name: Some("Hypersphere".to_string()),
-        }
-    }
+/// Index after removing all of `removed` (sorted) that are below it.
+fn shifted(index: usize, removed: &Vec<usize>) -> usize {
+    index - removed.iter().filter(|r| **r < index).count()
 }
 
 impl Add for Object {
@@ -252,11 +260,28 @@ impl Add for Object {
 impl AddAssign for Object {
     fn add_assign(&mut self, other: Self) {
         let count = self.vertices.len();
+        let e_count = self.edges.len();
+        let f_count = self.faces.len();
         for v in other.vertices {
             self.vertices.push(v.clone());
         }
         for e in other.edges {
             self.edges.push(Edge::new(e.a + count, e.b + count).clone_and_select(e.selected));
         }
+        for f in other.faces {
+            self.faces.push(Face {
+                vertices: f.vertices.iter().map(|i| i + count).collect(),
+                edges:    f.edges.iter().map(|i| i + e_count).collect(),
+                selected: f.selected,
+            });
+        }
+        for c in other.cells {
+            self.cells.push(Cell {
+                vertices: c.vertices.iter().map(|i| i + count).collect(),
+                edges:    c.edges.iter().map(|i| i + e_count).collect(),
+                faces:    c.faces.iter().map(|i| i + f_count).collect(),
+                selected: c.selected,
+            });
+        }
     }
 }
\ No newline at end of file
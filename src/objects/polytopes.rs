@@ -0,0 +1,193 @@
+use super::dist;
+use super::Edge;
+use super::Vec4f;
+use super::Object;
+use std::collections::HashSet;
+
+pub(super) const PHI: f32 = 1.618034; // golden ratio
+const RADIUS: f32 = 2.0; // circumradius, the same as the tesseract one
+const EPS: f32 = 1e-3;
+
+/// Sign changes and permutations (only even ones if `even`) of `c`, without duplicates.
+fn orbit(c: [f32; 4], even: bool) -> Vec<Vec4f> {
+    let mut res = vec![];
+    let mut known = HashSet::new();
+    for p in permutations(even) {
+        for signs in 0..16 {
+            let mut v = [0.0; 4];
+            for i in 0..4 {
+                let sign = if signs & (1 << i) == 0 { 1.0 } else { -1.0 };
+                v[i] = c[p[i]] * sign;
+            }
+            let key: Vec<i32> = v.iter().map(|x| (x * 1e4).round() as i32).collect();
+            if known.insert(key) { res.push(Vec4f::new(v[0], v[1], v[2], v[3])); }
+        }
+    }
+    res
+}
+
+fn permutations(even: bool) -> Vec<[usize; 4]> {
+    let mut res = vec![];
+    for a in 0..4 { for b in 0..4 { for c in 0..4 { for d in 0..4 {
+        let p = [a, b, c, d];
+        if (0..4).any(|i| (i + 1..4).any(|j| p[i] == p[j])) { continue; }
+        let inversions = (0..4).map(|i| (i + 1..4).filter(|j| p[i] > p[*j]).count()).sum::<usize>();
+        if !even || inversions % 2 == 0 { res.push(p); }
+    }}}}
+    res
+}
+
+fn cell600_vertices() -> Vec<Vec4f> {
+    let mut res = orbit([0.5, 0.5, 0.5, 0.5], false);
+    res.append(&mut orbit([1.0, 0.0, 0.0, 0.0], false));
+    // this ordering gives the 600-cell dual to the one of `cell120_vertices`
+    res.append(&mut orbit([0.5, PHI / 2.0, 0.5 / PHI, 0.0], true));
+    res
+}
+
+fn cell120_vertices() -> Vec<Vec4f> {
+    let (p, p2) = (PHI, PHI * PHI);
+    let s5 = 5f32.sqrt();
+    let mut res = orbit([0.0, 0.0, 2.0, 2.0], false);
+    res.append(&mut orbit([1.0, 1.0, 1.0, s5], false));
+    res.append(&mut orbit([1.0 / p2, p, p, p], false));
+    res.append(&mut orbit([1.0 / p, 1.0 / p, 1.0 / p, p2], false));
+    res.append(&mut orbit([0.0, 1.0 / p2, 1.0, p2], true));
+    res.append(&mut orbit([0.0, 1.0 / p, p, s5], true));
+    res.append(&mut orbit([1.0 / p, 1.0, p, 2.0], true));
+    res
+}
+
+/// Orders the vertices of a polygon into a loop following the edges.
+fn polygon_loop(vertices: &Vec<usize>, neighbors: &Vec<Vec<usize>>) -> Vec<usize> {
+    let mut res = vec![vertices[0]];
+    while res.len() < vertices.len() {
+        let last = res[res.len() - 1];
+        match neighbors[last].iter().find(|n| vertices.contains(n) && !res.contains(n)) {
+            Some(next) => res.push(*next),
+            None => break,
+        }
+    }
+    res
+}
+
+/// Builds a regular polytope from its vertices and the directions to the centers
+/// of its cells (the vertices of the dual polytope). Edges join the closest vertices,
+/// every cell holds the vertices farthest along its direction and two cells
+/// sharing at least three vertices meet in a face.
+fn regular(vertices: Vec<Vec4f>, cell_directions: Vec<Vec4f>, name: &str) -> Object {
+    let mut obj = Object::empty();
+    let scale = RADIUS / vertices[0].len();
+    obj.vertices = vertices.into_iter().map(|v| v * scale).collect();
+    obj.name = Some(name.to_string());
+
+    let n = obj.vertices.len();
+    let edge_len = (1..n)
+        .map(|i| dist(obj.vertices[0], obj.vertices[i]))
+        .fold(f32::MAX, f32::min);
+    let mut neighbors = vec![vec![]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            if (dist(obj.vertices[i], obj.vertices[j]) - edge_len).abs() < EPS * RADIUS {
+                obj.edges.push(Edge::new(i, j));
+                neighbors[i].push(j);
+                neighbors[j].push(i);
+            }
+        }
+    }
+
+    let cells: Vec<Vec<usize>> = cell_directions.iter().map(|u| {
+        let u = u.norm();
+        let max = obj.vertices.iter().map(|v| v.dot(u)).fold(f32::MIN, f32::max);
+        (0..n).filter(|i| obj.vertices[*i].dot(u) > max - EPS * RADIUS).collect()
+    }).collect();
+    let mut cell_faces = vec![vec![]; cells.len()];
+    for a in 0..cells.len() {
+        for b in a + 1..cells.len() {
+            let common: Vec<usize> = cells[a].iter().filter(|v| cells[b].contains(v)).copied().collect();
+            if common.len() < 3 { continue; }
+            let face = obj.add_face(polygon_loop(&common, &neighbors));
+            cell_faces[a].push(face);
+            cell_faces[b].push(face);
+        }
+    }
+    for faces in cell_faces { obj.add_cell(faces); }
+    obj
+}
+
+impl Object {
+    /// Pentachoron: 5 vertices, 10 edges, 10 triangles, 5 tetrahedra.
+    pub fn cell5() -> Self {
+        let s = 1.0 / 5f32.sqrt();
+        let vertices = vec![
+            Vec4f::new( 1.0,  1.0,  1.0, -s),
+            Vec4f::new( 1.0, -1.0, -1.0, -s),
+            Vec4f::new(-1.0,  1.0, -1.0, -s),
+            Vec4f::new(-1.0, -1.0,  1.0, -s),
+            Vec4f::new( 0.0,  0.0,  0.0, 4.0 * s),
+        ];
+        let directions = vertices.iter().map(|v| *v * -1.0).collect();
+        regular(vertices, directions, "5-cell")
+    }
+
+    /// Hexadecachoron: 8 vertices, 24 edges, 32 triangles, 16 tetrahedra.
+    pub fn cell16() -> Self {
+        regular(orbit([1.0, 0.0, 0.0, 0.0], false), orbit([1.0, 1.0, 1.0, 1.0], false), "16-cell")
+    }
+
+    /// Icositetrachoron: 24 vertices, 96 edges, 96 triangles, 24 octahedra.
+    pub fn cell24() -> Self {
+        let mut directions = orbit([1.0, 0.0, 0.0, 0.0], false);
+        directions.append(&mut orbit([0.5, 0.5, 0.5, 0.5], false));
+        regular(orbit([1.0, 1.0, 0.0, 0.0], false), directions, "24-cell")
+    }
+
+    /// Hecatonicosachoron: 600 vertices, 1200 edges, 720 pentagons, 120 dodecahedra.
+    pub fn cell120() -> Self {
+        regular(cell120_vertices(), cell600_vertices(), "120-cell")
+    }
+
+    /// Hexacosichoron: 120 vertices, 720 edges, 1200 triangles, 600 tetrahedra.
+    pub fn cell600() -> Self {
+        regular(cell600_vertices(), cell120_vertices(), "600-cell")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    fn edge_lengths_equal(obj: &Object) -> bool {
+        let len = |e: &Edge| dist(obj.vertices[e.a], obj.vertices[e.b]);
+        let first = len(&obj.edges[0]);
+        obj.edges.iter().all(|e| (len(e) - first).abs() < EPS)
+    }
+
+    #[test]
+    fn regular_polytopes() {
+        let polytopes = [
+            (Object::cell5(), (5, 10, 10, 5), 3, 4),
+            (Object::cell16(), (8, 24, 32, 16), 3, 4),
+            (Object::cell24(), (24, 96, 96, 24), 3, 8),
+            (Object::cell120(), (600, 1200, 720, 120), 5, 12),
+            (Object::cell600(), (120, 720, 1200, 600), 3, 4),
+        ];
+        for (obj, expected, face_size, cell_size) in polytopes.iter() {
+            assert_eq!(counts(obj), *expected, "{:?}", obj.name);
+            assert!(edge_lengths_equal(obj), "{:?}", obj.name);
+            assert!(obj.faces.iter().all(|f| f.vertices.len() == *face_size), "{:?}", obj.name);
+            assert!(obj.cells.iter().all(|c| c.faces.len() == *cell_size), "{:?}", obj.name);
+        }
+    }
+
+    #[test]
+    fn tesseract() {
+        let obj = Object::tesseract();
+        assert_eq!((obj.vertices.len(), obj.edges.len()), (16, 32));
+        assert!(edge_lengths_equal(&obj));
+    }
+}
@@ -0,0 +1,170 @@
+use super::Vec4f;
+use super::Object;
+use std::f32::consts::PI;
+
+/// Vertices of a regular polygon in the XY plane, `z` and `w` given.
+fn polygon(n: usize, edge: f32, z: f32, w: f32, phase: f32) -> Vec<Vec4f> {
+    let r = edge / (2.0 * (PI / n as f32).sin());
+    (0..n)
+        .map(|i| {
+            let a = 2.0 * PI * i as f32 / n as f32 + phase;
+            Vec4f::new(r * a.cos(), r * a.sin(), z, w)
+        })
+        .collect()
+}
+
+impl Object {
+    /// Uniform p-gonal prism on the `w = 0` hyperplane, the polygons lie at `z = ±edge / 2`.
+    pub fn prism3d(p: usize, edge: f32) -> Self {
+        let p = p.max(3);
+        let mut obj = Object::empty();
+        obj.vertices = polygon(p, edge, -edge / 2.0, 0.0, 0.0);
+        obj.vertices.append(&mut polygon(p, edge, edge / 2.0, 0.0, 0.0));
+        let mut faces = vec![
+            obj.add_face((0..p).collect()),
+            obj.add_face((p..2 * p).collect()),
+        ];
+        for i in 0..p {
+            let j = (i + 1) % p;
+            faces.push(obj.add_face(vec![i, j, j + p, i + p]));
+        }
+        obj.add_cell(faces);
+        obj.name = Some(format!("{}-prism", p));
+        obj
+    }
+
+    /// Uniform p-gonal antiprism on the `w = 0` hyperplane: two polygons turned
+    /// by half a step against each other, joined by equilateral triangles.
+    pub fn antiprism3d(p: usize, edge: f32) -> Self {
+        let p = p.max(3);
+        let r = edge / (2.0 * (PI / p as f32).sin());
+        let chord = 2.0 * r * (PI / (2 * p) as f32).sin();
+        let h = (edge * edge - chord * chord).sqrt();
+        let mut obj = Object::empty();
+        obj.vertices = polygon(p, edge, -h / 2.0, 0.0, 0.0);
+        obj.vertices.append(&mut polygon(p, edge, h / 2.0, 0.0, PI / p as f32));
+        let mut faces = vec![
+            obj.add_face((0..p).collect()),
+            obj.add_face((p..2 * p).collect()),
+        ];
+        for i in 0..p {
+            let j = (i + 1) % p;
+            faces.push(obj.add_face(vec![i, j, i + p]));
+            faces.push(obj.add_face(vec![i + p, j, j + p]));
+        }
+        obj.add_cell(faces);
+        obj.name = Some(format!("{}-antiprism", p));
+        obj
+    }
+
+    /// Prism over a 3D solid: copies at `w -= edge / 2` and `w += edge / 2`,
+    /// every vertex joined with its copy, every edge gives a square and every face
+    /// a prism cell. Solid faces without cells are treated as the boundary of one cell.
+    pub fn polyhedral_prism(solid: &Object, edge: f32) -> Self {
+        let n = solid.vertices.len();
+        let f_count = solid.faces.len();
+        let mut bottom = solid.clone();
+        let mut top = solid.clone();
+        for v in &mut bottom.vertices { v.w -= edge / 2.0; }
+        for v in &mut top.vertices { v.w += edge / 2.0; }
+        if bottom.cells.is_empty() && f_count > 0 {
+            bottom.add_cell((0..f_count).collect());
+            top.add_cell((0..f_count).collect());
+        }
+        let mut obj = bottom;
+        obj += top;
+        let mut squares = vec![];
+        for e in &solid.edges {
+            squares.push(obj.add_face(vec![e.a, e.b, e.b + n, e.a + n]));
+        }
+        for (i, f) in solid.faces.iter().enumerate() {
+            let mut faces = vec![i, i + f_count];
+            faces.extend(f.edges.iter().map(|e| squares[*e]));
+            obj.add_cell(faces);
+        }
+        obj.name = solid.name.as_ref().map(|name| format!("{} prism", name));
+        obj
+    }
+
+    /// p-gonal prismatic prism: `prism3d` extruded along W.
+    pub fn prism(p: usize, edge: f32) -> Self {
+        Object::polyhedral_prism(&Object::prism3d(p, edge), edge)
+    }
+
+    /// p-gonal antiprismatic prism: `antiprism3d` extruded along W.
+    pub fn antiprism(p: usize, edge: f32) -> Self {
+        Object::polyhedral_prism(&Object::antiprism3d(p, edge), edge)
+    }
+
+    /// p,q-duoprism, the product of a p-gon in the XY plane and a q-gon in the ZW plane:
+    /// p·q vertices, p·q squares, p q-gons and q p-gons, p q-gonal and q p-gonal prisms.
+    pub fn duoprism(p: usize, q: usize, edge: f32) -> Self {
+        let (p, q) = (p.max(3), q.max(3));
+        let a = polygon(p, edge, 0.0, 0.0, 0.0);
+        let b = polygon(q, edge, 0.0, 0.0, 0.0);
+        let mut obj = Object::empty();
+        for u in &a {
+            for v in &b { obj.vertices.push(Vec4f::new(u.x, u.y, v.x, v.y)); }
+        }
+        let at = |i: usize, j: usize| (i % p) * q + j % q;
+        let mut squares = vec![];
+        for i in 0..p {
+            for j in 0..q {
+                squares.push(obj.add_face(vec![at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)]));
+            }
+        }
+        let q_gons: Vec<usize> = (0..p).map(|i| obj.add_face((0..q).map(|j| at(i, j)).collect())).collect();
+        let p_gons: Vec<usize> = (0..q).map(|j| obj.add_face((0..p).map(|i| at(i, j)).collect())).collect();
+        for i in 0..p {
+            let mut faces = vec![q_gons[i], q_gons[(i + 1) % p]];
+            faces.extend((0..q).map(|j| squares[i * q + j]));
+            obj.add_cell(faces);
+        }
+        for j in 0..q {
+            let mut faces = vec![p_gons[j], p_gons[(j + 1) % q]];
+            faces.extend((0..p).map(|i| squares[i * q + j]));
+            obj.add_cell(faces);
+        }
+        obj.name = Some(format!("{},{}-duoprism", p, q));
+        obj
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::dist;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    fn edge_lengths(obj: &Object, edge: f32) -> bool {
+        obj.edges.iter().all(|e| (dist(obj.vertices[e.a], obj.vertices[e.b]) - edge).abs() < 1e-4)
+    }
+
+    /// Every edge of every cell belongs to exactly two of its faces.
+    fn closed_cells(obj: &Object) -> bool {
+        obj.cells.iter().all(|c| c.edges.iter().all(|e| {
+            c.faces.iter().filter(|f| obj.faces[**f].edges.contains(e)).count() == 2
+        }))
+    }
+
+    #[test]
+    fn prisms() {
+        for p in 3..8 {
+            let shapes = [
+                (Object::prism3d(p, 1.5), (2 * p, 3 * p, p + 2, 1)),
+                (Object::antiprism3d(p, 1.5), (2 * p, 4 * p, 2 * p + 2, 1)),
+                (Object::prism(p, 1.5), (4 * p, 8 * p, 5 * p + 4, p + 4)),
+                (Object::antiprism(p, 1.5), (4 * p, 10 * p, 8 * p + 4, 2 * p + 4)),
+                (Object::duoprism(p, 4, 1.5), (4 * p, 8 * p, 5 * p + 4, p + 4)),
+            ];
+            for (obj, expected) in shapes.iter() {
+                assert_eq!(counts(obj), *expected, "{:?}", obj.name);
+                assert!(edge_lengths(obj, 1.5), "{:?}", obj.name);
+                assert!(closed_cells(obj), "{:?}", obj.name);
+            }
+        }
+    }
+}
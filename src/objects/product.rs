@@ -0,0 +1,237 @@
+use super::Edge;
+use super::Face;
+use super::Vec4f;
+use super::Object;
+
+impl Object {
+    /// Cartesian product: every pair of elements gives an element of the summed
+    /// dimension (vertex × edge is an edge, edge × edge a square, face × edge
+    /// a prism cell...), elements above cells are left out. Vertices are added
+    /// as vectors, so the operands should lie in complementary subspaces,
+    /// e.g. a square in XY times a square in ZW is a tesseract.
+    pub fn product(&self, other: &Object) -> Object {
+        let (a, b) = (self, other);
+        let (nb, eb, fb) = (b.vertices.len(), b.edges.len(), b.faces.len());
+        let (ea, fa) = (a.edges.len(), a.faces.len());
+        let vertex = |i: usize, j: usize| i * nb + j;
+        let edge_a = |e: usize, j: usize| e * nb + j; // edge of `a` at vertex j of `b`
+        let edge_b = |i: usize, e: usize| ea * nb + i * eb + e; // vertex i of `a` at edge of `b`
+        let face_a = |f: usize, j: usize| f * nb + j;
+        let square = |e: usize, g: usize| fa * nb + e * eb + g;
+        let face_b = |i: usize, f: usize| fa * nb + ea * eb + i * fb + f;
+
+        let mut obj = Object::empty();
+        for u in &a.vertices {
+            for v in &b.vertices { obj.vertices.push(Vec4f::new(u.x + v.x, u.y + v.y, u.z + v.z, u.w + v.w)); }
+        }
+        for e in &a.edges {
+            for j in 0..nb { obj.edges.push(Edge::new(vertex(e.a, j), vertex(e.b, j))); }
+        }
+        for i in 0..a.vertices.len() {
+            for e in &b.edges { obj.edges.push(Edge::new(vertex(i, e.a), vertex(i, e.b))); }
+        }
+        for f in &a.faces {
+            for j in 0..nb {
+                obj.faces.push(Face::new(
+                    f.vertices.iter().map(|v| vertex(*v, j)).collect(),
+                    f.edges.iter().map(|e| edge_a(*e, j)).collect(),
+                ));
+            }
+        }
+        for (ei, e) in a.edges.iter().enumerate() {
+            for (gi, g) in b.edges.iter().enumerate() {
+                obj.faces.push(Face::new(
+                    vec![vertex(e.a, g.a), vertex(e.b, g.a), vertex(e.b, g.b), vertex(e.a, g.b)],
+                    vec![edge_a(ei, g.a), edge_b(e.b, gi), edge_a(ei, g.b), edge_b(e.a, gi)],
+                ));
+            }
+        }
+        for i in 0..a.vertices.len() {
+            for f in &b.faces {
+                obj.faces.push(Face::new(
+                    f.vertices.iter().map(|v| vertex(i, *v)).collect(),
+                    f.edges.iter().map(|e| edge_b(i, *e)).collect(),
+                ));
+            }
+        }
+        for c in &a.cells {
+            for j in 0..nb { obj.add_cell(c.faces.iter().map(|f| face_a(*f, j)).collect()); }
+        }
+        for (fi, f) in a.faces.iter().enumerate() {
+            for (gi, g) in b.edges.iter().enumerate() {
+                let mut faces = vec![face_a(fi, g.a), face_a(fi, g.b)];
+                faces.extend(f.edges.iter().map(|e| square(*e, gi)));
+                obj.add_cell(faces);
+            }
+        }
+        for (ei, e) in a.edges.iter().enumerate() {
+            for (fi, f) in b.faces.iter().enumerate() {
+                let mut faces = vec![face_b(e.a, fi), face_b(e.b, fi)];
+                faces.extend(f.edges.iter().map(|g| square(ei, *g)));
+                obj.add_cell(faces);
+            }
+        }
+        for i in 0..a.vertices.len() {
+            for c in &b.cells { obj.add_cell(c.faces.iter().map(|f| face_b(i, *f)).collect()); }
+        }
+        obj.name = join_names(a, b, "x");
+        obj
+    }
+
+    /// Join: both objects plus an element spanning every pair of their elements
+    /// (vertex and vertex give an edge, edge and vertex a triangle, face and vertex
+    /// a pyramid cell, edge and edge a tetrahedron), elements above cells are left
+    /// out. Vertices keep their positions, so the operands should lie in skew subspaces.
+    pub fn join(&self, other: &Object) -> Object {
+        let (a, b) = (self, other);
+        let na = a.vertices.len();
+        let mut obj = a.clone();
+        obj += b.clone();
+        let (ea, fa) = (a.edges.len(), a.faces.len());
+        let eb = b.edges.len();
+        let mut pair_edge = vec![vec![0; b.vertices.len()]; na];
+        for i in 0..na {
+            for j in 0..b.vertices.len() {
+                obj.edges.push(Edge::new(i, na + j));
+                pair_edge[i][j] = obj.edges.len() - 1;
+            }
+        }
+        // triangles over an edge of `a` and a vertex of `b` and the other way round
+        let mut tri_a = vec![vec![0; b.vertices.len()]; ea];
+        for (ei, e) in a.edges.iter().enumerate() {
+            for j in 0..b.vertices.len() {
+                obj.faces.push(Face::new(
+                    vec![e.a, e.b, na + j],
+                    vec![ei, pair_edge[e.b][j], pair_edge[e.a][j]],
+                ));
+                tri_a[ei][j] = obj.faces.len() - 1;
+            }
+        }
+        let mut tri_b = vec![vec![0; eb]; na];
+        for i in 0..na {
+            for (gi, g) in b.edges.iter().enumerate() {
+                obj.faces.push(Face::new(
+                    vec![na + g.a, na + g.b, i],
+                    vec![ea + gi, pair_edge[i][g.b], pair_edge[i][g.a]],
+                ));
+                tri_b[i][gi] = obj.faces.len() - 1;
+            }
+        }
+        for (fi, f) in a.faces.iter().enumerate() {
+            for j in 0..b.vertices.len() {
+                let mut faces = vec![fi];
+                faces.extend(f.edges.iter().map(|e| tri_a[*e][j]));
+                obj.add_cell(faces);
+            }
+        }
+        for (ei, e) in a.edges.iter().enumerate() {
+            for (gi, g) in b.edges.iter().enumerate() {
+                obj.add_cell(vec![tri_a[ei][g.a], tri_a[ei][g.b], tri_b[e.a][gi], tri_b[e.b][gi]]);
+            }
+        }
+        for i in 0..na {
+            for (fi, f) in b.faces.iter().enumerate() {
+                let mut faces = vec![fa + fi];
+                faces.extend(f.edges.iter().map(|g| tri_b[i][*g]));
+                obj.add_cell(faces);
+            }
+        }
+        obj.name = join_names(a, b, "v");
+        obj
+    }
+
+    /// Pyramid: join with a single apex vertex.
+    pub fn pyramid(&self, apex: Vec4f) -> Object {
+        let mut point = Object::empty();
+        point.vertices.push(apex);
+        let mut obj = self.join(&point);
+        obj.name = self.name.as_ref().map(|name| format!("{} pyramid", name));
+        obj
+    }
+
+    /// Tegum (dual) product: the join of the boundaries of both objects.
+    /// An object with a single cell, or no cells and a single face, or no faces
+    /// and a single edge is a solid, its body is not part of the boundary.
+    /// Vertices keep their positions, so the operands should lie in complementary
+    /// subspaces around the origin, e.g. two squares in XY and ZW give a 16-cell.
+    pub fn tegum(&self, other: &Object) -> Object {
+        let mut obj = self.boundary().join(&other.boundary());
+        obj.name = join_names(self, other, "+");
+        obj
+    }
+
+    fn boundary(&self) -> Object {
+        let mut obj = self.clone();
+        if obj.cells.len() == 1 { obj.cells.clear(); }
+        else if obj.cells.is_empty() && obj.faces.len() == 1 { obj.faces.clear(); }
+        else if obj.faces.is_empty() && obj.edges.len() == 1 { obj.edges.clear(); }
+        obj
+    }
+}
+
+fn join_names(a: &Object, b: &Object, op: &str) -> Option<String> {
+    match (&a.name, &b.name) {
+        (Some(a), Some(b)) => Some(format!("{} {} {}", a, op, b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    /// Filled polygon with the given vertices.
+    fn polygon(vertices: Vec<Vec4f>) -> Object {
+        let mut obj = Object::empty();
+        let n = vertices.len();
+        obj.vertices = vertices;
+        obj.add_face((0..n).collect());
+        obj
+    }
+
+    fn square_xy() -> Object {
+        polygon(vec![
+            Vec4f::new(-1.0, -1.0, 0.0, 0.0),
+            Vec4f::new( 1.0, -1.0, 0.0, 0.0),
+            Vec4f::new( 1.0,  1.0, 0.0, 0.0),
+            Vec4f::new(-1.0,  1.0, 0.0, 0.0),
+        ])
+    }
+
+    fn square_zw() -> Object {
+        let mut obj = square_xy();
+        for v in &mut obj.vertices { *v = Vec4f::new(0.0, 0.0, v.x, v.y); }
+        obj
+    }
+
+    #[test]
+    fn tesseract() {
+        let obj = square_xy().product(&square_zw());
+        assert_eq!(counts(&obj), (16, 32, 24, 8));
+        assert!(obj.cells.iter().all(|c| c.faces.len() == 6 && c.vertices.len() == 8));
+    }
+
+    #[test]
+    fn tetrahedron() {
+        let triangle = polygon(vec![
+            Vec4f::new(0.0, 0.0, 0.0, 0.0),
+            Vec4f::new(1.0, 0.0, 0.0, 0.0),
+            Vec4f::new(0.0, 1.0, 0.0, 0.0),
+        ]);
+        let obj = triangle.pyramid(Vec4f::new(0.0, 0.0, 1.0, 0.0));
+        assert_eq!(counts(&obj), (4, 6, 4, 1));
+        assert!(obj.faces.iter().all(|f| f.vertices.len() == 3));
+        assert_eq!(obj.cells[0].faces.len(), 4);
+    }
+
+    #[test]
+    fn cell16() {
+        let obj = square_xy().tegum(&square_zw());
+        assert_eq!(counts(&obj), (8, 24, 32, 16));
+        assert!(obj.cells.iter().all(|c| c.faces.len() == 4 && c.vertices.len() == 4));
+    }
+}
@@ -0,0 +1,190 @@
+use super::Vec4f;
+use super::Object;
+use super::UNIT_AXES;
+use std::collections::HashMap;
+
+const EPS: f32 = 1e-5;
+
+/// Orthonormal basis of the hyperplane orthogonal to the unit `normal`,
+/// the standard axes closest to the hyperplane come first.
+fn hyperplane_basis(normal: Vec4f) -> Vec<Vec4f> {
+    let skip = (0..4)
+        .max_by(|a, b| UNIT_AXES[*a].dot(normal).abs().partial_cmp(&UNIT_AXES[*b].dot(normal).abs()).unwrap())
+        .unwrap();
+    let mut basis: Vec<Vec4f> = vec![];
+    for (i, a) in UNIT_AXES.iter().enumerate() {
+        if i == skip { continue; }
+        let mut r = *a - normal * a.dot(normal);
+        for b in &basis { r = r - *b * r.dot(*b); }
+        basis.push(r.norm());
+    }
+    basis
+}
+
+/// Splits segments into closed loops of points, open chains are dropped.
+fn loops(segments: &Vec<(usize, usize)>) -> Vec<Vec<usize>> {
+    let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (a, b) in segments {
+        neighbors.entry(*a).or_insert_with(Vec::new).push(*b);
+        neighbors.entry(*b).or_insert_with(Vec::new).push(*a);
+    }
+    let mut starts: Vec<usize> = neighbors.keys().copied().collect();
+    starts.sort();
+    let mut used = vec![];
+    let mut res = vec![];
+    for start in starts {
+        if used.contains(&start) || neighbors[&start].len() != 2 { continue; }
+        let mut lp = vec![start];
+        loop {
+            let last = lp[lp.len() - 1];
+            let next = neighbors[&last].iter().find(|v| !lp.contains(v) && neighbors[v].len() == 2);
+            match next {
+                Some(next) => lp.push(*next),
+                None => break,
+            }
+        }
+        used.extend(lp.iter().copied());
+        let closed = neighbors[&lp[lp.len() - 1]].contains(&start);
+        if lp.len() >= 3 && closed { res.push(lp); }
+    }
+    res
+}
+
+impl Object {
+    /// Cross-section with the hyperplane `normal · p = offset`. Vertices of the
+    /// result are given in an orthonormal basis of the hyperplane (X, Y, Z for
+    /// the W normal) with `w = 0`. Edges crossing the hyperplane give vertices,
+    /// faces give edges, cells give faces, and a closed surface of these faces
+    /// gets a cell. Elements lying in the hyperplane are kept as they are.
+    pub fn slice(&self, normal: Vec4f, offset: f32) -> Object {
+        let mut obj = Object::empty();
+        obj.name = self.name.as_ref().map(|name| format!("{} slice", name));
+        if normal.len() < EPS { return obj; }
+        let normal = normal.norm();
+        let basis = hyperplane_basis(normal);
+        let scale = self.vertices.iter().map(|v| v.len()).fold(1.0, f32::max);
+        let eps = EPS * scale;
+        let side: Vec<f32> = self.vertices.iter().map(|v| v.dot(normal) - offset).collect();
+        let on = |i: usize| side[i].abs() <= eps;
+
+        let project = |p: Vec4f, obj: &mut Object| {
+            obj.vertices.push(Vec4f::new(p.dot(basis[0]), p.dot(basis[1]), p.dot(basis[2]), 0.0));
+            obj.vertices.len() - 1
+        };
+        let mut vertex_points = vec![None; self.vertices.len()];
+        for (i, v) in self.vertices.iter().enumerate() {
+            if on(i) { vertex_points[i] = Some(project(*v, &mut obj)); }
+        }
+        let mut edge_points = vec![None; self.edges.len()];
+        for (i, e) in self.edges.iter().enumerate() {
+            let (a, b) = (e.a, e.b);
+            edge_points[i] = if on(a) || on(b) {
+                vertex_points[a].or(vertex_points[b])
+            } else if (side[a] < 0.0) != (side[b] < 0.0) {
+                let t = side[a] / (side[a] - side[b]);
+                Some(project(self.vertices[a] + (self.vertices[b] - self.vertices[a]) * t, &mut obj))
+            } else {
+                None
+            };
+            if let (Some(a), Some(b)) = (vertex_points[a], vertex_points[b]) { obj.add_edge(a, b); }
+        }
+
+        // a face gives segments between consecutive crossing points along the cut line
+        let mut face_segments = vec![vec![]; self.faces.len()];
+        let mut face_in_plane = vec![None; self.faces.len()];
+        for (i, f) in self.faces.iter().enumerate() {
+            if f.vertices.iter().all(|v| on(*v)) {
+                let lp: Vec<usize> = f.vertices.iter().filter_map(|v| vertex_points[*v]).collect();
+                let face = match obj.find_face(&lp) {
+                    Some(face) => face,
+                    None => obj.add_face(lp),
+                };
+                face_in_plane[i] = Some(face);
+                continue;
+            }
+            let mut points: Vec<usize> = vec![];
+            for e in &f.edges {
+                if let Some(p) = edge_points[*e] {
+                    if !points.contains(&p) { points.push(p); }
+                }
+            }
+            if points.len() < 2 { continue; }
+            let (first, last) = (obj.vertices[points[0]], obj.vertices[points[points.len() - 1]]);
+            let dir = last - first;
+            points.sort_by(|a, b| {
+                let (da, db) = (obj.vertices[*a].dot(dir), obj.vertices[*b].dot(dir));
+                da.partial_cmp(&db).unwrap()
+            });
+            for pair in points.chunks(2) {
+                if pair.len() == 2 {
+                    obj.add_edge(pair[0], pair[1]);
+                    face_segments[i].push((pair[0], pair[1]));
+                }
+            }
+        }
+
+        // a cell gives polygons bounded by the segments of its faces
+        let mut slice_faces = vec![];
+        for c in &self.cells {
+            if c.faces.iter().all(|f| face_in_plane[*f].is_some()) {
+                obj.add_cell(c.faces.iter().filter_map(|f| face_in_plane[*f]).collect());
+                continue;
+            }
+            let mut segments: Vec<(usize, usize)> = vec![];
+            for (a, b) in c.faces.iter().flat_map(|f| face_segments[*f].iter()) {
+                // faces on both sides of the hyperplane give the same segment
+                let segment = (*a.min(b), *a.max(b));
+                if !segments.contains(&segment) { segments.push(segment); }
+            }
+            for lp in loops(&segments) {
+                let face = match obj.find_face(&lp) {
+                    Some(face) => face,
+                    None => obj.add_face(lp),
+                };
+                if !slice_faces.contains(&face) { slice_faces.push(face); }
+            }
+        }
+
+        let mut sharing: HashMap<usize, usize> = HashMap::new();
+        for f in &slice_faces {
+            for e in &obj.faces[*f].edges { *sharing.entry(*e).or_insert(0) += 1; }
+        }
+        if obj.cells.is_empty() && slice_faces.len() >= 4 && sharing.values().all(|n| *n == 2) {
+            obj.add_cell(slice_faces);
+        }
+        obj
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(obj: &Object) -> (usize, usize, usize, usize) {
+        (obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len())
+    }
+
+    /// Tesseract with its squares and cubes.
+    fn tesseract() -> Object {
+        let mut obj = Object::tesseract();
+        let all: Vec<usize> = (0..obj.vertices.len()).collect();
+        obj.fill_hull(&all, &mut |_, _| {});
+        obj
+    }
+
+    #[test]
+    fn cube() {
+        let obj = tesseract().slice(UNIT_AXES[3], 0.0);
+        assert_eq!(counts(&obj), (8, 12, 6, 1));
+        assert!(obj.vertices.iter().all(|v| {
+            v.x.abs() == 1.0 && v.y.abs() == 1.0 && v.z.abs() == 1.0 && v.w == 0.0
+        }));
+        assert_eq!(obj.cells[0].faces.len(), 6);
+    }
+
+    #[test]
+    fn outside() {
+        let obj = tesseract().slice(UNIT_AXES[3], 1.5);
+        assert_eq!(counts(&obj), (0, 0, 0, 0));
+    }
+}
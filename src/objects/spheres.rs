@@ -0,0 +1,216 @@
+use super::Edge;
+use super::Face;
+use super::Vec4f;
+use super::Object;
+use super::polytopes::PHI;
+use std::f32::consts::PI;
+use std::collections::HashMap;
+
+/// Builds an object from triangles, every edge is created once.
+fn from_triangles(vertices: Vec<Vec4f>, triangles: Vec<[usize; 3]>, name: &str) -> Object {
+    let mut obj = Object::empty();
+    obj.vertices = vertices;
+    obj.name = Some(name.to_string());
+    let mut known = HashMap::new();
+    for t in triangles {
+        let mut edges = vec![];
+        for i in 0..3 {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            let key = (a.min(b), a.max(b));
+            let e = *known.entry(key).or_insert_with(|| {
+                obj.edges.push(Edge::new(a, b));
+                obj.edges.len() - 1
+            });
+            edges.push(e);
+        }
+        obj.faces.push(Face::new(t.to_vec(), edges));
+    }
+    obj
+}
+
+/// Unit 2-sphere as (x, y, z) points: the poles and `rings - 1` latitudes
+/// of `segments` points each, covered with triangles.
+fn uv_template(rings: usize, segments: usize) -> (Vec<(f32, f32, f32)>, Vec<[usize; 3]>) {
+    let mut points = vec![(0.0, 1.0, 0.0)];
+    for i in 1..rings {
+        let t = PI * i as f32 / rings as f32;
+        for j in 0..segments {
+            let p = 2.0 * PI * j as f32 / segments as f32;
+            points.push((t.sin() * p.cos(), t.cos(), t.sin() * p.sin()));
+        }
+    }
+    points.push((0.0, -1.0, 0.0));
+    let south = points.len() - 1;
+    let at = |i: usize, j: usize| 1 + (i - 1) * segments + j % segments;
+    let mut triangles = vec![];
+    for j in 0..segments {
+        triangles.push([0, at(1, j), at(1, j + 1)]);
+        for i in 1..rings - 1 {
+            triangles.push([at(i, j), at(i + 1, j), at(i + 1, j + 1)]);
+            triangles.push([at(i, j), at(i + 1, j + 1), at(i, j + 1)]);
+        }
+        triangles.push([south, at(rings - 1, j + 1), at(rings - 1, j)]);
+    }
+    (points, triangles)
+}
+
+/// Splits every triangle into four `subdivisions` times and pushes
+/// all vertices onto the sphere of the given radius.
+fn geodesic(base: &Object, subdivisions: usize, radius: f32, name: &str) -> Object {
+    let mut vertices: Vec<Vec4f> = base.vertices.iter().map(|v| v.norm() * radius).collect();
+    let mut triangles: Vec<[usize; 3]> = base.faces
+        .iter()
+        .map(|f| [f.vertices[0], f.vertices[1], f.vertices[2]])
+        .collect();
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut mid = |a: usize, b: usize, vertices: &mut Vec<Vec4f>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                vertices.push(((vertices[a] + vertices[b]) / 2.0).norm() * radius);
+                vertices.len() - 1
+            })
+        };
+        let mut next = Vec::with_capacity(triangles.len() * 4);
+        for [a, b, c] in triangles {
+            let ab = mid(a, b, &mut vertices);
+            let bc = mid(b, c, &mut vertices);
+            let ca = mid(c, a, &mut vertices);
+            next.push([a, ab, ca]);
+            next.push([ab, b, bc]);
+            next.push([ca, bc, c]);
+            next.push([ab, bc, ca]);
+        }
+        triangles = next;
+    }
+    from_triangles(vertices, triangles, name)
+}
+
+fn icosahedron() -> Object {
+    let mut obj = Object::empty();
+    for (a, b) in vec![(1.0, PHI), (1.0, -PHI), (-1.0, PHI), (-1.0, -PHI)] {
+        obj.vertices.push(Vec4f::new(0.0, a, b, 0.0));
+        obj.vertices.push(Vec4f::new(a, b, 0.0, 0.0));
+        obj.vertices.push(Vec4f::new(b, 0.0, a, 0.0));
+    }
+    let close = |i: usize, j: usize| (obj.vertices[i] - obj.vertices[j]).len() < 2.1;
+    let mut faces = vec![];
+    for i in 0..12 {
+        for j in i + 1..12 {
+            for k in j + 1..12 {
+                if close(i, j) && close(j, k) && close(i, k) { faces.push(vec![i, j, k]); }
+            }
+        }
+    }
+    for f in faces { obj.add_face(f); }
+    obj
+}
+
+impl Object {
+    /// 2-sphere on the `w = 0` hyperplane: two poles and `rings - 1` latitudes
+    /// of `segments` vertices, triangle faces.
+    pub fn uv_sphere(radius: f32, rings: usize, segments: usize) -> Self {
+        let (rings, segments) = (rings.max(2), segments.max(3));
+        let (points, triangles) = uv_template(rings, segments);
+        let vertices = points
+            .iter()
+            .map(|(x, y, z)| Vec4f::new(x * radius, y * radius, z * radius, 0.0))
+            .collect();
+        from_triangles(vertices, triangles, "Sphere 3D")
+    }
+
+    /// 2-sphere on the `w = 0` hyperplane made of a subdivided icosahedron.
+    pub fn geodesic_sphere(radius: f32, subdivisions: usize) -> Self {
+        geodesic(&icosahedron(), subdivisions, radius, "Geodesic sphere 3D")
+    }
+
+    /// 3-sphere made of `rings - 1` UV 2-spheres (see `uv_sphere`) stacked
+    /// along W between two poles. Matching vertices of neighbouring
+    /// 2-spheres are joined, every side quad is split into two triangles.
+    pub fn uv_glome(radius: f32, rings: usize, segments: usize) -> Self {
+        let (rings, segments) = (rings.max(2), segments.max(3));
+        let (points, sphere_triangles) = uv_template(rings, segments);
+        let n = points.len();
+        let sphere = from_triangles(vec![Vec4f::new0(); n], sphere_triangles.clone(), "");
+        let mut vertices = vec![Vec4f::new(0.0, 0.0, 0.0, radius)];
+        for k in 1..rings {
+            let t = PI * k as f32 / rings as f32;
+            let (r, w) = (radius * t.sin(), radius * t.cos());
+            for (x, y, z) in &points {
+                vertices.push(Vec4f::new(x * r, y * r, z * r, w));
+            }
+        }
+        vertices.push(Vec4f::new(0.0, 0.0, 0.0, -radius));
+        let south = vertices.len() - 1;
+        let at = |k: usize, i: usize| 1 + (k - 1) * n + i;
+        let mut triangles = vec![];
+        for k in 1..rings {
+            for t in &sphere_triangles {
+                triangles.push([at(k, t[0]), at(k, t[1]), at(k, t[2])]);
+            }
+        }
+        for e in &sphere.edges {
+            triangles.push([0, at(1, e.a), at(1, e.b)]);
+            for k in 1..rings - 1 {
+                triangles.push([at(k, e.a), at(k, e.b), at(k + 1, e.b)]);
+                triangles.push([at(k, e.a), at(k + 1, e.b), at(k + 1, e.a)]);
+            }
+            triangles.push([south, at(rings - 1, e.b), at(rings - 1, e.a)]);
+        }
+        from_triangles(vertices, triangles, "Hypersphere")
+    }
+
+    /// 3-sphere made of a subdivided 600-cell.
+    pub fn geodesic_glome(radius: f32, subdivisions: usize) -> Self {
+        geodesic(&Object::cell600(), subdivisions, radius, "Geodesic hypersphere")
+    }
+
+    pub fn sphere3d() -> Self {
+        Object::uv_sphere(1.5, 10, 20)
+    }
+
+    pub fn sphere4d() -> Self {
+        Object::uv_glome(1.5, 6, 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn distinct(obj: &Object) -> bool {
+        let mut known = HashSet::new();
+        obj.vertices.iter().all(|v| {
+            let key: Vec<i32> = [v.x, v.y, v.z, v.w].iter().map(|c| (c * 1e4).round() as i32).collect();
+            known.insert(key)
+        })
+    }
+
+    fn on_sphere(obj: &Object, radius: f32) -> bool {
+        obj.vertices.iter().all(|v| (v.len() - radius).abs() < 1e-3)
+    }
+
+    #[test]
+    fn spheres() {
+        let spheres = [
+            (Object::uv_sphere(1.5, 10, 20), 2 + 9 * 20),
+            (Object::geodesic_sphere(1.5, 2), 162),
+            (Object::uv_glome(1.5, 6, 10), 2 + 5 * (2 + 5 * 10)),
+            (Object::geodesic_glome(1.5, 1), 120 + 720),
+        ];
+        for (obj, vertices) in spheres.iter() {
+            assert_eq!(obj.vertices.len(), *vertices, "{:?}", obj.name);
+            assert!(distinct(obj), "{:?}", obj.name);
+            assert!(on_sphere(obj, 1.5), "{:?}", obj.name);
+        }
+    }
+
+    #[test]
+    fn closed_surface() {
+        // Euler characteristic of the 2-sphere
+        for obj in [Object::uv_sphere(1.0, 4, 6), Object::geodesic_sphere(1.0, 1)].iter() {
+            let (v, e, f) = (obj.vertices.len(), obj.edges.len(), obj.faces.len());
+            assert_eq!(v + f, e + 2, "{:?}", obj.name);
+        }
+    }
+}
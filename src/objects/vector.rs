@@ -6,10 +6,22 @@ use std::ops::Sub;
 use std::ops::Mul;
 use std::clone::Clone;
 
-use crate::angle::Angle;
+use crate::rotor::Rotor;
+use super::Camera;
 
 pub const SCALE: f32 = 3000.0;
 
+/// Names of the coordinate axes, in the order of `Vec4f` fields.
+pub const AXIS_NAMES: [&str; 4] = ["X", "Y", "Z", "W"];
+
+/// Unit vectors along the coordinate axes, in the same order.
+pub const UNIT_AXES: [Vec4f; 4] = [
+    Vec4f::new(1.0, 0.0, 0.0, 0.0),
+    Vec4f::new(0.0, 1.0, 0.0, 0.0),
+    Vec4f::new(0.0, 0.0, 1.0, 0.0),
+    Vec4f::new(0.0, 0.0, 0.0, 1.0),
+];
+
 pub fn dist(v1: Vec4f, v2: Vec4f) -> f32 {
     ((v1.x - v2.x).powf(2.0) + (v1.y - v2.y).powf(2.0) + (v1.z - v2.z).powf(2.0) + (v1.w - v2.w).powf(2.0)).sqrt()
 }
@@ -42,7 +54,7 @@ pub struct Vec4f {
 }
 
 impl Vec4f {
-    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vec4f {
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Vec4f {
         Vec4f {
             x,
             y,
@@ -134,21 +146,15 @@ impl Vec4f {
         }
     }
 
-    pub fn calc(&mut self, a: &Angle, d: f32, window: &Window) -> Vec4f {
-        let rotated = self.rotated_xy(&a.xy)
-                                 .rotated_xz(&a.xz)
-                                 .rotated_xw(&a.xw)
-                                 .rotated_yz(&a.yz)
-                                 .rotated_yw(&a.yw)
-                                 .rotated_zw(&a.zw);
-        let w = 1.0 / (d - rotated.w);
-        let x = rotated.x * w;
-        let y = rotated.y * w;
-        let z = rotated.z * w;
-        let proj3d = (x, y, z);
-        let z = 1.0 / (d - rotated.w - proj3d.2) * SCALE;
-        let x = proj3d.0 * z + window.config().w / 2.0;
-        let y = proj3d.1 * z + window.config().h / 2.0;
+    /// Projects the vertex, turned by `r`, through the camera onto
+    /// a viewport of the given size (width, height).
+    pub fn project(self, r: &Rotor, camera: &Camera, size: (f32, f32)) -> (f32, f32) {
+        camera.project(r.apply(self), size)
+    }
+
+    pub fn calc(&mut self, r: &Rotor, camera: &Camera, window: &Window) -> Vec4f {
+        let conf = window.config();
+        let (x, y) = self.project(r, camera, (conf.w, conf.h));
         self.set_proj((x, y));
         self.with_proj((x, y))
     }
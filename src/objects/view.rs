@@ -0,0 +1,168 @@
+use super::Vec4f;
+use super::Object;
+use super::hull::convex_hull;
+
+const EPS: f32 = 1e-3; // relative to the edge length
+
+/// Kind of element a view preset looks at.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Element {
+    Vertex,
+    Edge,
+    Face,
+    Cell,
+}
+
+/// Solves the 4×4 system `m · x = b`, `None` if it is singular.
+fn solve4(mut m: [[f32; 4]; 4], mut b: [f32; 4]) -> Option<[f32; 4]> {
+    let scale = (0..4).map(|i| m[i][i].abs()).fold(0.0, f32::max);
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|i, j| m[*i][col].abs().partial_cmp(&m[*j][col].abs()).unwrap())?;
+        if m[pivot][col].abs() <= 1e-4 * scale { return None; }
+        m.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in 0..4 {
+            if row == col { continue; }
+            let k = m[row][col] / m[col][col];
+            for i in col..4 { m[row][i] -= k * m[col][i]; }
+            b[row] -= k * b[col];
+        }
+    }
+    Some([b[0] / m[0][0], b[1] / m[1][1], b[2] / m[2][2], b[3] / m[3][3]])
+}
+
+/// Gram matrix of four vectors.
+fn gram(v: &[Vec4f]) -> [[f32; 4]; 4] {
+    let mut g = [[0.0; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 { g[i][j] = v[i].dot(v[j]); }
+    }
+    g
+}
+
+impl Object {
+    fn centroid(&self, vertices: &[usize]) -> Vec4f {
+        let sum = vertices.iter().fold(Vec4f::new0(), |c, v| c + self.vertices[*v]);
+        sum / vertices.len().max(1) as f32
+    }
+
+    /// Centers of the elements of the given kind, each with whether it is
+    /// selected. A face or a cell counts as selected when all its vertices are.
+    /// Faces and cells of the convex hull are taken when the object has none.
+    fn element_centers(&self, element: Element) -> Vec<(Vec4f, bool)> {
+        let all_selected = |vertices: &[usize]| vertices.iter().all(|v| self.vertices[*v].selected);
+        match element {
+            Element::Vertex => self.vertices.iter().map(|v| (*v, v.selected)).collect(),
+            Element::Edge => self.edges.iter()
+                .map(|e| (self.centroid(&[e.a, e.b]), e.selected))
+                .collect(),
+            Element::Face if !self.faces.is_empty() => self.faces.iter()
+                .map(|f| (self.centroid(&f.vertices), f.selected || all_selected(&f.vertices)))
+                .collect(),
+            Element::Cell if !self.cells.is_empty() => self.cells.iter()
+                .map(|c| (self.centroid(&c.vertices), c.selected || all_selected(&c.vertices)))
+                .collect(),
+            _ => {
+                let hull = convex_hull(&self.vertices, &mut |_, _| {});
+                let faces = hull.faces.iter().map(|f| f.clone());
+                let cells = hull.cells.iter().map(|c| {
+                    let mut vertices: Vec<usize> = c.iter().flat_map(|f| hull.faces[*f].iter().copied()).collect();
+                    vertices.sort();
+                    vertices.dedup();
+                    vertices
+                });
+                let elements: Vec<Vec<usize>> = if element == Element::Face { faces.collect() } else { cells.collect() };
+                elements.iter().map(|v| (self.centroid(v), all_selected(v))).collect()
+            }
+        }
+    }
+
+    /// Direction from the center of the object to the selected elements of
+    /// the given kind, or to the one farthest along `toward` if none is selected.
+    pub fn element_direction(&self, element: Element, toward: Vec4f) -> Option<Vec4f> {
+        if self.vertices.is_empty() { return None; }
+        let all: Vec<usize> = (0..self.vertices.len()).collect();
+        let center = self.centroid(&all);
+        let centers: Vec<(Vec4f, bool)> = self.element_centers(element)
+            .into_iter()
+            .map(|(c, selected)| (c - center, selected))
+            .collect();
+        let selected: Vec<Vec4f> = centers.iter().filter(|c| c.1).map(|c| c.0).collect();
+        let dir = if selected.is_empty() {
+            centers.iter()
+                .map(|c| c.0)
+                .max_by(|a, b| a.dot(toward).partial_cmp(&b.dot(toward)).unwrap())?
+        } else {
+            selected.iter().fold(Vec4f::new0(), |s, c| s + *c) / selected.len() as f32
+        };
+        if dir.len() < EPS { None } else { Some(dir.norm()) }
+    }
+
+    /// Orthonormal basis of the Coxeter plane, where the Petrie polygon of the
+    /// object is a regular polygon. The Petrie polygon is found as the longest
+    /// closed edge path turned by one orthogonal map at every step: any four
+    /// consecutive edges span the space, the next one is the same combination
+    /// of the previous four. Assumes all vertices look alike, as in uniform
+    /// polytopes, and starts at the first vertex.
+    pub fn coxeter_plane(&self) -> Option<(Vec4f, Vec4f)> {
+        let mut neighbors = vec![vec![]; self.vertices.len()];
+        for e in &self.edges {
+            neighbors[e.a].push(e.b);
+            neighbors[e.b].push(e.a);
+        }
+        let start = neighbors.iter().position(|n| !n.is_empty())?;
+        let length = self.vertices[start] - self.vertices[neighbors[start][0]];
+        let eps = EPS * length.len();
+        let mut best: Vec<Vec4f> = vec![];
+        let mut stack: Vec<Vec<usize>> = neighbors[start].iter().map(|v| vec![start, *v]).collect();
+        while let Some(walk) = stack.pop() {
+            if walk.len() < 6 {
+                let (last, prev) = (walk[walk.len() - 1], walk[walk.len() - 2]);
+                for next in neighbors[last].iter().filter(|v| **v != prev) {
+                    let mut longer = walk.clone();
+                    longer.push(*next);
+                    stack.push(longer);
+                }
+                continue;
+            }
+            if let Some(polygon) = self.petrie_polygon(&walk, &neighbors, eps) {
+                if polygon.len() > best.len() { best = polygon; }
+            }
+        }
+        if best.is_empty() { return None; }
+        let h = best.len() as f32;
+        let angle = |k: usize| 2.0 * std::f32::consts::PI * k as f32 / h;
+        let u = best.iter().enumerate().fold(Vec4f::new0(), |s, (k, e)| s + *e * angle(k).cos());
+        let w = best.iter().enumerate().fold(Vec4f::new0(), |s, (k, e)| s + *e * angle(k).sin());
+        let w = w - u.norm() * w.dot(u.norm());
+        if u.len() < eps || w.len() < eps { return None; }
+        Some((u.norm(), w.norm()))
+    }
+
+    /// Edges of the closed path continuing the first five edges of `walk`
+    /// by the recurrence of `coxeter_plane`, `None` if it leaves the edges of
+    /// the object or does not close.
+    fn petrie_polygon(&self, walk: &[usize], neighbors: &Vec<Vec<usize>>, eps: f32) -> Option<Vec<Vec4f>> {
+        let mut edges: Vec<Vec4f> = (0..5).map(|k| self.vertices[walk[k + 1]] - self.vertices[walk[k]]).collect();
+        let (g0, g1) = (gram(&edges[0..4]), gram(&edges[1..5]));
+        let tol = eps * edges[0].len();
+        for i in 0..4 {
+            for j in 0..4 {
+                if (g0[i][j] - g1[i][j]).abs() > tol { return None; }
+            }
+        }
+        let b = [edges[0].dot(edges[4]), edges[1].dot(edges[4]), edges[2].dot(edges[4]), edges[3].dot(edges[4])];
+        let c = solve4(g0, b)?;
+        let mut last = walk[5];
+        loop {
+            let n = edges.len();
+            let e = (0..4).fold(Vec4f::new0(), |s, k| s + edges[n - 4 + k] * c[k]);
+            if last == walk[0] && (e - edges[0]).len() < eps { return Some(edges); }
+            if n > self.vertices.len() * 2 { return None; }
+            let target = self.vertices[last] + e;
+            let next = *neighbors[last].iter().find(|v| (self.vertices[**v] - target).len() < eps)?;
+            edges.push(self.vertices[next] - self.vertices[last]);
+            last = next;
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use crate::error::*;
+use crate::import::open_file;
+use crate::objects::Edge;
+use crate::objects::Vec4f;
+use crate::objects::Object;
+
+fn corrupted() -> Error {
+    Error::Import(ImportError::FileCorrupted)
+}
+
+fn numbers<T: std::str::FromStr>(line: &str) -> Result<Vec<T>, Error> {
+    line.split_whitespace().map(|t| t.parse().map_err(|_| corrupted())).collect()
+}
+
+/// Reads a Geomview / Stella4D 4OFF file:
+/// ```text
+/// 4OFF
+/// NVertices NFaces NEdges [NCells]
+/// x y z w                  (NVertices lines)
+/// n v1 v2 ... vn [color]   (NFaces lines, vertex loops)
+/// n f1 f2 ... fn [color]   (NCells lines, face indices, only with NCells)
+/// ```
+/// Edges are derived from the faces, `NEdges` is ignored.
+/// Faces of two vertices are read as plain edges. Comments start with '#'.
+pub fn read_off<R: Read>(reader: &mut R) -> Result<Object, Error> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(Error::Io)?;
+    let mut lines = text
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty());
+    let header = lines.next().ok_or(Error::Import(ImportError::InvalidExtention))?;
+    let rest = match header.strip_prefix("4OFF") {
+        Some(rest) => rest.trim(),
+        None => return Err(Error::Import(ImportError::InvalidExtention)),
+    };
+    let counts: Vec<usize> = if rest.is_empty() {
+        numbers(lines.next().ok_or_else(corrupted)?)?
+    } else {
+        numbers(rest)?
+    };
+    if counts.len() < 3 { return Err(corrupted()); }
+    let (vertices_count, faces_count) = (counts[0], counts[1]);
+    let cells_count = counts.get(3).copied().unwrap_or(0);
+
+    let mut obj = Object::empty();
+    for _ in 0..vertices_count {
+        let c: Vec<f32> = numbers(lines.next().ok_or_else(corrupted)?)?;
+        if c.len() < 4 { return Err(corrupted()); }
+        obj.vertices.push(Vec4f::new(c[0], c[1], c[2], c[3]));
+    }
+    let mut face_map = vec![]; // file face index -> object face index
+    for _ in 0..faces_count {
+        let line = lines.next().ok_or_else(corrupted)?;
+        let mut tokens = line.split_whitespace();
+        let n: usize = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(corrupted)?;
+        let mut indices = vec![];
+        for _ in 0..n {
+            let i: usize = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(corrupted)?;
+            if i >= vertices_count { return Err(corrupted()); }
+            indices.push(i);
+        }
+        if n == 2 {
+            obj.add_edge(indices[0], indices[1]);
+            face_map.push(None);
+            continue;
+        }
+        if n < 3 { return Err(corrupted()); }
+        face_map.push(Some(obj.add_face(indices)));
+    }
+    for _ in 0..cells_count {
+        let line = lines.next().ok_or_else(corrupted)?;
+        let mut tokens = line.split_whitespace();
+        let n: usize = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(corrupted)?;
+        let mut faces = vec![];
+        for _ in 0..n {
+            let i: usize = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(corrupted)?;
+            match face_map.get(i) {
+                Some(Some(f)) => faces.push(*f),
+                _ => return Err(corrupted()),
+            }
+        }
+        obj.add_cell(faces);
+    }
+    Ok(obj)
+}
+
+/// Writes all objects as one 4OFF mesh with cells, see `read_off`.
+/// Edges that belong to no face are written as faces of two vertices.
+pub fn write_off<W: Write>(writer: &mut W, objects: &Vec<Object>) -> Result<(), Error> {
+    let mut obj = Object::empty();
+    for o in objects { obj += o.clone(); }
+    let mut in_face = vec![false; obj.edges.len()];
+    for f in &obj.faces {
+        for e in &f.edges { in_face[*e] = true; }
+    }
+    let loose: Vec<&Edge> = obj.edges.iter().enumerate()
+        .filter(|(i, _)| !in_face[*i])
+        .map(|(_, e)| e)
+        .collect();
+    let mut text = String::from("4OFF\n# Vertices, Faces, Edges, Cells\n");
+    text += &format!(
+        "{} {} {} {}\n",
+        obj.vertices.len(),
+        obj.faces.len() + loose.len(),
+        obj.edges.len(),
+        obj.cells.len(),
+    );
+    for v in &obj.vertices {
+        text += &format!("{} {} {} {}\n", v.x, v.y, v.z, v.w);
+    }
+    text += "# Faces\n";
+    for f in &obj.faces {
+        let indices: Vec<String> = f.vertices.iter().map(|i| i.to_string()).collect();
+        text += &format!("{} {}\n", indices.len(), indices.join(" "));
+    }
+    for e in loose {
+        text += &format!("2 {} {}\n", e.a, e.b);
+    }
+    if !obj.cells.is_empty() {
+        text += "# Cells\n";
+    }
+    for c in &obj.cells {
+        let indices: Vec<String> = c.faces.iter().map(|i| i.to_string()).collect();
+        text += &format!("{} {}\n", indices.len(), indices.join(" "));
+    }
+    writer.write_all(text.as_bytes()).map_err(Error::Io)
+}
+
+pub fn load_off_from_path<P: AsRef<Path>>(path: P) -> Result<Object, Error> {
+    read_off(&mut open_file(path)?)
+}
+
+pub fn save_off_to_path<P: AsRef<Path>>(path: P, objects: &Vec<Object>) -> Result<(), Error> {
+    let mut file = File::create(path).map_err(Error::Io)?;
+    write_off(&mut file, objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &str) -> Result<Object, Error> {
+        read_off(&mut text.as_bytes())
+    }
+
+    /// Tetrahedron with one cell and a loose edge to a fifth vertex.
+    const TETRAHEDRON: &str = "4OFF
+        # Vertices, Faces, Edges, Cells
+        5 5 7 1
+        0 0 0 0
+        1 0 0 0
+        0 1 0 0
+        0 0 1 0
+        0 0 0 1
+        3 0 1 2
+        3 0 1 3
+        3 0 2 3  # color is ignored: 1 0 0
+        3 1 2 3
+        2 3 4
+        4 0 1 2 3
+    ";
+
+    #[test]
+    fn read() {
+        let obj = match load(TETRAHEDRON) {
+            Ok(obj) => obj,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!((obj.vertices.len(), obj.edges.len(), obj.faces.len(), obj.cells.len()), (5, 7, 4, 1));
+        assert_eq!(obj.faces[2].vertices, vec![0, 2, 3]);
+        assert_eq!((obj.cells[0].vertices.len(), obj.cells[0].edges.len()), (4, 6));
+        assert!(obj.find_edge(4, 3).is_some());
+    }
+
+    #[test]
+    fn round_trip() {
+        let obj = match load(TETRAHEDRON) {
+            Ok(obj) => obj,
+            Err(e) => panic!("{}", e),
+        };
+        let mut text = vec![];
+        assert!(write_off(&mut text, &vec![obj.clone()]).is_ok());
+        let loaded = match read_off(&mut &text[..]) {
+            Ok(obj) => obj,
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(loaded.vertices, obj.vertices);
+        let pairs = |o: &Object| o.edges.iter().map(|e| (e.a, e.b)).collect::<Vec<_>>();
+        assert_eq!(pairs(&loaded), pairs(&obj));
+        assert_eq!(loaded.faces, obj.faces);
+        assert_eq!(loaded.cells, obj.cells);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(load("OFF\n3 1 0\n"), Err(Error::Import(ImportError::InvalidExtention))));
+        assert!(matches!(load("4OFF 2 1 0\n0 0 0 0\n"), Err(Error::Import(ImportError::FileCorrupted))));
+        assert!(matches!(load("4OFF 1 1 0\n0 0 0 0\n3 0 0 1\n"), Err(Error::Import(ImportError::FileCorrupted))));
+        assert!(matches!(load("4OFF 3 1 0 1\n0 0 0 0\n1 0 0 0\n0 1 0 0\n3 0 1 2\n1 1\n"), Err(Error::Import(ImportError::FileCorrupted))));
+    }
+}
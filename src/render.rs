@@ -0,0 +1,158 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use crate::draw::*;
+use crate::rotor::Rotor;
+use crate::error::Error;
+use crate::objects::Object;
+use crate::objects::Camera;
+use macroquad::prelude::Color;
+
+/// RGBA image rasterised on the CPU, so no window or GPU context is needed.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width:  u32,
+    pub height: u32,
+    pub pixels: Vec<u8>, // RGBA, row by row
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&to_bytes(background));
+        }
+        Image { width, height, pixels }
+    }
+
+    /// Alpha-blends `color` over the pixel, `coverage` scales the color alpha.
+    fn blend(&mut self, x: i64, y: i64, color: Color, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 { return; }
+        let a = (color.a * coverage).max(0.0).min(1.0);
+        if a == 0.0 { return; }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let src = to_bytes(color);
+        for c in 0..3 {
+            let dst = self.pixels[i + c] as f32;
+            self.pixels[i + c] = (src[c] as f32 * a + dst * (1.0 - a)).round() as u8;
+        }
+        let dst_a = self.pixels[i + 3] as f32 / 255.0;
+        self.pixels[i + 3] = ((a + dst_a * (1.0 - a)) * 255.0).round() as u8;
+    }
+
+    /// Same semantics as macroquad `draw_line`: `thickness` is the full line width.
+    pub fn draw_line(&mut self, a: (f32, f32), b: (f32, f32), thickness: f32, color: Color) {
+        let r = thickness / 2.0;
+        let x0 = (a.0.min(b.0) - r - 1.0).floor() as i64;
+        let x1 = (a.0.max(b.0) + r + 1.0).ceil() as i64;
+        let y0 = (a.1.min(b.1) - r - 1.0).floor() as i64;
+        let y1 = (a.1.max(b.1) + r + 1.0).ceil() as i64;
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len2 = dx * dx + dy * dy;
+        for y in y0.max(0)..=y1.min(self.height as i64 - 1) {
+            for x in x0.max(0)..=x1.min(self.width as i64 - 1) {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let t = if len2 > 0.0 {
+                    (((px - a.0) * dx + (py - a.1) * dy) / len2).max(0.0).min(1.0)
+                } else { 0.0 };
+                let (cx, cy) = (a.0 + dx * t, a.1 + dy * t);
+                let d = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+                self.blend(x, y, color, r + 0.5 - d);
+            }
+        }
+    }
+
+    /// Same semantics as macroquad `draw_circle`: a filled disk of radius `r`.
+    pub fn draw_circle(&mut self, c: (f32, f32), r: f32, color: Color) {
+        let x0 = (c.0 - r - 1.0).floor() as i64;
+        let x1 = (c.0 + r + 1.0).ceil() as i64;
+        let y0 = (c.1 - r - 1.0).floor() as i64;
+        let y1 = (c.1 + r + 1.0).ceil() as i64;
+        for y in y0.max(0)..=y1.min(self.height as i64 - 1) {
+            for x in x0.max(0)..=x1.min(self.width as i64 - 1) {
+                let d = ((x as f32 + 0.5 - c.0).powi(2) + (y as f32 + 0.5 - c.1).powi(2)).sqrt();
+                self.blend(x, y, color, r + 0.5 - d);
+            }
+        }
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&self.pixels).map_err(png_error)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let file = File::create(path).map_err(Error::Io)?;
+        self.write_png(std::io::BufWriter::new(file))
+    }
+}
+
+fn to_bytes(color: Color) -> [u8; 4] {
+    [
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8,
+        (color.a * 255.0).round() as u8,
+    ]
+}
+
+fn png_error(e: png::EncodingError) -> Error {
+    match e {
+        png::EncodingError::IoError(e) => Error::Io(e),
+        e => Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)),
+    }
+}
+
+/// Renders objects the way the viewport does (`draw_edges`, `draw_vertices`)
+/// through the given camera.
+pub fn render(
+    objects:       &Vec<Object>,
+    rotor:         &Rotor,
+    camera:        &Camera,
+    size:          (u32, u32),
+    show_vertices: bool,
+) -> Image {
+    let mut image = Image::new(size.0, size.1, BACKGROUND_COLOR);
+    let viewport = (size.0 as f32, size.1 as f32);
+    let mut camera = *camera;
+    camera.fit(objects.iter().flat_map(|o| o.vertices.iter()), rotor);
+    let camera = &camera;
+    for obj in objects {
+        let proj: Vec<(f32, f32)> = obj.vertices
+            .iter()
+            .map(|v| v.project(rotor, camera, viewport))
+            .collect();
+        for e in &obj.edges {
+            let (a, b) = (proj[e.a], proj[e.b]);
+            if e.selected {
+                image.draw_line(a, b, 2.0, SELECTED_EDGE_OUTLINE);
+                image.draw_line(a, b, 1.0, SELECTED_EDGE_COLOR);
+            } else {
+                image.draw_line(a, b, 1.0, EDGE_COLOR);
+            }
+        }
+        if !show_vertices { continue; }
+        for (i, v) in obj.vertices.iter().enumerate() {
+            if v.selected {
+                image.draw_circle(proj[i], 3.0, SELECTED_VERTEX_OUTLINE);
+                image.draw_circle(proj[i], 2.0, SELECTED_VERTEX_COLOR);
+            } else {
+                image.draw_circle(proj[i], 2.0, VERTEX_COLOR);
+            }
+        }
+    }
+    image
+}
+
+pub fn render_to_png<P: AsRef<Path>>(
+    path:    P,
+    objects: &Vec<Object>,
+    rotor:   &Rotor,
+    camera:  &Camera,
+    size:    (u32, u32),
+) -> Result<(), Error> {
+    render(objects, rotor, camera, size, true).save_png(path)
+}
@@ -0,0 +1,345 @@
+use std::ops::Mul;
+use crate::angle::Angle;
+use crate::angle::Plane;
+use crate::objects::Vec4f;
+
+/// 4×4 matrix, rows and columns in X, Y, Z, W order; it maps `v` to `m · v`.
+pub type Matrix4 = [[f32; 4]; 4];
+
+fn to_array(v: Vec4f) -> [f32; 4] {
+    [v.x, v.y, v.z, v.w]
+}
+
+/// Matrix of `Vec4f::rotated_xy` and the other plane rotations.
+fn plane_matrix(plane: Plane, angle: f32) -> Matrix4 {
+    let (i, j) = plane.axes();
+    let mut m = [[0.0; 4]; 4];
+    for k in 0..4 { m[k][k] = 1.0; }
+    m[i][i] = angle.cos();
+    m[i][j] = angle.sin();
+    m[j][i] = -angle.sin();
+    m[j][j] = angle.cos();
+    m
+}
+
+fn transposed(m: &Matrix4) -> Matrix4 {
+    let mut t = [[0.0; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 { t[i][j] = m[j][i]; }
+    }
+    t
+}
+
+fn product(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+    let mut m = [[0.0; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 { m[i][j] = (0..4).map(|k| a[i][k] * b[k][j]).sum(); }
+    }
+    m
+}
+
+/// Quaternion `w + xi + yj + zk`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quat {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quat {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Quat { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Quat::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    fn from_array(a: [f32; 4]) -> Self {
+        Quat::new(a[0], a[1], a[2], a[3])
+    }
+
+    fn as_array(self) -> [f32; 4] {
+        [self.w, self.x, self.y, self.z]
+    }
+
+    pub fn conj(self) -> Self {
+        Quat::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn dot(self, q: Quat) -> f32 {
+        self.w * q.w + self.x * q.x + self.y * q.y + self.z * q.z
+    }
+
+    pub fn norm(self) -> Self {
+        let len = self.dot(self).sqrt();
+        Quat::new(self.w / len, self.x / len, self.y / len, self.z / len)
+    }
+
+    /// Interpolates unit quaternions along the great arc, as they are:
+    /// `q` and `-q` lead along different arcs.
+    pub fn slerp(self, q: Quat, t: f32) -> Self {
+        let cos = self.dot(q).max(-1.0).min(1.0);
+        let angle = cos.acos();
+        if angle.sin().abs() < 1e-5 {
+            let a = self.as_array();
+            let b = q.as_array();
+            return Quat::from_array([0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)).norm();
+        }
+        let (ka, kb) = (((1.0 - t) * angle).sin() / angle.sin(), (t * angle).sin() / angle.sin());
+        Quat::new(
+            self.w * ka + q.w * kb,
+            self.x * ka + q.x * kb,
+            self.y * ka + q.y * kb,
+            self.z * ka + q.z * kb,
+        )
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+    fn mul(self, q: Self) -> Self {
+        Quat::new(
+            self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z,
+            self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y,
+            self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x,
+            self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w,
+        )
+    }
+}
+
+/// 4D orientation as a pair of unit quaternions: a point, taken as the
+/// quaternion `w + xi + yj + zk`, goes to `left · p · right`. Either half alone
+/// turns all of space by one angle in two orthogonal planes (an isoclinic
+/// rotation). `(left, right)` and `(-left, -right)` are the same orientation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rotor {
+    pub left:  Quat,
+    pub right: Quat,
+}
+
+impl Rotor {
+    pub fn identity() -> Self {
+        Rotor { left: Quat::identity(), right: Quat::identity() }
+    }
+
+    /// Rotation by `angle` in one plane, in the same sense as the planes of `Angle`.
+    pub fn from_plane(plane: Plane, angle: f32) -> Self {
+        Rotor::from_matrix(&plane_matrix(plane, angle))
+    }
+
+    /// Rotation by `angle` in the plane of `a` and `b` that turns `a` toward `b`.
+    pub fn in_plane(a: Vec4f, b: Vec4f, angle: f32) -> Self {
+        let a = a.norm();
+        let b = b - a * b.dot(a);
+        if b.len() < 1e-6 { return Rotor::identity(); }
+        let (a, b) = (to_array(a), to_array(b.norm()));
+        let (cos, sin) = (angle.cos(), angle.sin());
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                let identity = if i == j { 1.0 } else { 0.0 };
+                m[i][j] = identity + (cos - 1.0) * (a[i] * a[j] + b[i] * b[j]) + sin * (b[i] * a[j] - a[i] * b[j]);
+            }
+        }
+        Rotor::from_matrix(&m)
+    }
+
+    /// The smallest rotation turning the direction of `from` into the direction of `to`.
+    pub fn between(from: Vec4f, to: Vec4f) -> Self {
+        let cos = from.norm().dot(to.norm()).max(-1.0).min(1.0);
+        Rotor::in_plane(from, to, cos.acos())
+    }
+
+    /// Rotation taking every vector of the orthonormal basis `from` to the one
+    /// of `to` with the same index. Both bases must have the same handedness.
+    pub fn from_bases(from: &[Vec4f; 4], to: &[Vec4f; 4]) -> Self {
+        let (from, to) = (from.map(to_array), to.map(to_array));
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 { m[i][j] = (0..4).map(|k| to[k][i] * from[k][j]).sum(); }
+        }
+        Rotor::from_matrix(&m)
+    }
+
+    /// Rotor of an orthonormal matrix with determinant 1. The matrix is split
+    /// into its left and right parts through the associate matrix, which is
+    /// the outer product of `left` and `right`.
+    pub fn from_matrix(m: &Matrix4) -> Self {
+        let unit = |i: usize| {
+            let mut a = [0.0; 4];
+            a[i] = 1.0;
+            Quat::from_array(a)
+        };
+        let mut associate = [[0.0; 4]; 4];
+        for a in 0..4 {
+            for b in 0..4 {
+                // the 16 maps p -> e_a · p · e_b are orthogonal with squared norm 4
+                let basis = Rotor { left: unit(a), right: unit(b) };
+                for j in 0..4 {
+                    let mut e = [0.0; 4];
+                    e[j] = 1.0;
+                    let image = to_array(basis.apply(Vec4f::new(e[0], e[1], e[2], e[3])));
+                    associate[a][b] += (0..4).map(|i| image[i] * m[i][j]).sum::<f32>() / 4.0;
+                }
+            }
+        }
+        // every column is a multiple of `left`, the one with the largest entry the most exact
+        let mut best = (0, 0);
+        for a in 0..4 {
+            for b in 0..4 {
+                if associate[a][b].abs() > associate[best.0][best.1].abs() { best = (a, b); }
+            }
+        }
+        let left = Quat::from_array([0, 1, 2, 3].map(|a| associate[a][best.1])).norm();
+        let right = Quat::from_array([0, 1, 2, 3].map(|b| {
+            (0..4).map(|a| associate[a][b] * left.as_array()[a]).sum()
+        })).norm();
+        Rotor { left, right }
+    }
+
+    pub fn to_matrix(&self) -> Matrix4 {
+        let columns = [
+            self.apply(Vec4f::new(1.0, 0.0, 0.0, 0.0)),
+            self.apply(Vec4f::new(0.0, 1.0, 0.0, 0.0)),
+            self.apply(Vec4f::new(0.0, 0.0, 1.0, 0.0)),
+            self.apply(Vec4f::new(0.0, 0.0, 0.0, 1.0)),
+        ];
+        transposed(&columns.map(to_array))
+    }
+
+    pub fn apply(&self, v: Vec4f) -> Vec4f {
+        let p = self.left * Quat::new(v.w, v.x, v.y, v.z) * self.right;
+        Vec4f::new(p.x, p.y, p.z, p.w)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Rotor { left: self.left.conj(), right: self.right.conj() }
+    }
+
+    /// Scales both halves back to unit length, against the rounding errors
+    /// that build up over many compositions.
+    pub fn norm(&self) -> Self {
+        Rotor { left: self.left.norm(), right: self.right.norm() }
+    }
+
+    /// Interpolates from `self` (t = 0) to `other` (t = 1) with a constant
+    /// angular speed of both halves, taking the shorter of the two ways.
+    pub fn slerp(&self, other: &Rotor, t: f32) -> Self {
+        let arc = |a: f32, b: f32| a.max(-1.0).min(1.0).acos() + b.max(-1.0).min(1.0).acos();
+        let (l, r) = (self.left.dot(other.left), self.right.dot(other.right));
+        let sign = if arc(l, r) <= arc(-l, -r) { 1.0 } else { -1.0 };
+        let flip = |q: Quat| Quat::new(q.w * sign, q.x * sign, q.y * sign, q.z * sign);
+        Rotor {
+            left:  self.left.slerp(flip(other.left), t),
+            right: self.right.slerp(flip(other.right), t),
+        }
+    }
+
+    /// Plane angles that `Vec4f::rotated` turns into this orientation. The
+    /// matrix is reduced to the identity by one rotation per plane, in the
+    /// order `Vec4f::rotated` applies them.
+    pub fn to_angle(&self) -> Angle {
+        let mut n = transposed(&self.to_matrix());
+        let mut angle = Angle::new();
+        let steps = [(Plane::XY, 0), (Plane::XZ, 0), (Plane::XW, 0), (Plane::YZ, 1), (Plane::YW, 1), (Plane::ZW, 2)];
+        for (plane, column) in steps.iter() {
+            let (i, j) = plane.axes();
+            let value = n[j][*column].atan2(n[i][*column]);
+            n = product(&plane_matrix(*plane, value), &n);
+            angle.set(*plane, value);
+        }
+        angle
+    }
+}
+
+impl From<Angle> for Rotor {
+    fn from(a: Angle) -> Self {
+        Plane::ALL.iter().fold(Rotor::identity(), |r, p| Rotor::from_plane(*p, a.get(*p)) * r)
+    }
+}
+
+/// `(a * b).apply(v)` is `a.apply(b.apply(v))`.
+impl Mul for Rotor {
+    type Output = Self;
+    fn mul(self, b: Self) -> Self {
+        Rotor { left: self.left * b.left, right: b.right * self.right }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Orientations with every plane angle in [-1, 1], away from the
+    /// singularities of `to_angle`.
+    fn angles() -> Vec<Angle> {
+        (0..20).map(|k| {
+            let mut a = Angle::new();
+            for (i, p) in Plane::ALL.iter().enumerate() {
+                a.set(*p, ((k * 7 + i * 3) % 11) as f32 / 5.0 - 1.0);
+            }
+            a
+        }).collect()
+    }
+
+    fn identity() -> Matrix4 {
+        Rotor::identity().to_matrix()
+    }
+
+    fn close(a: &Matrix4, b: &Matrix4) -> bool {
+        (0..4).all(|i| (0..4).all(|j| (a[i][j] - b[i][j]).abs() < 1e-4))
+    }
+
+    #[test]
+    fn inverse() {
+        for a in angles() {
+            let r = Rotor::from(a);
+            assert!(close(&(r * r.inverse()).to_matrix(), &identity()));
+            assert!(close(&(r.inverse() * r).to_matrix(), &identity()));
+        }
+    }
+
+    #[test]
+    fn matrix() {
+        for a in angles() {
+            let m = Plane::ALL.iter().fold(identity(), |m, p| product(&plane_matrix(*p, a.get(*p)), &m));
+            assert!(close(&Rotor::from_matrix(&m).to_matrix(), &m));
+            let v = Vec4f::new(0.3, -1.0, 2.0, 0.5);
+            assert!((Rotor::from_matrix(&m).apply(v) - v.rotated(a)).len() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn to_angle() {
+        for a in angles() {
+            let b = Rotor::from(a).to_angle();
+            for p in Plane::ALL.iter() {
+                assert!((a.get(*p) - b.get(*p)).abs() < 1e-3, "{:?}: {} != {}", p, a.get(*p), b.get(*p));
+            }
+        }
+    }
+
+    #[test]
+    fn between() {
+        let from = Vec4f::new(1.0, 2.0, 0.0, -1.0);
+        let to = Vec4f::new(0.0, -1.0, 3.0, 0.5);
+        let r = Rotor::between(from, to);
+        assert!((r.apply(from.norm()) - to.norm()).len() < 1e-4);
+        // vectors orthogonal to the plane of the turn stay in place
+        let fixed = Vec4f::new(0.0, 1.0, 0.0, 2.0);
+        assert!(fixed.dot(from).abs() < 1e-6 && fixed.dot(to).abs() < 1e-6);
+        assert!((r.apply(fixed) - fixed).len() < 1e-4);
+    }
+
+    #[test]
+    fn slerp() {
+        let angles = angles();
+        for (a, b) in angles.iter().zip(angles.iter().skip(1)) {
+            let (a, b) = (Rotor::from(*a), Rotor::from(*b));
+            assert!(close(&a.slerp(&b, 0.0).to_matrix(), &a.to_matrix()));
+            assert!(close(&a.slerp(&b, 1.0).to_matrix(), &b.to_matrix()));
+        }
+    }
+}
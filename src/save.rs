@@ -1,180 +1,228 @@
-use macroquad::prelude::show_mouse;
+use crate::dialog::show_dialog;
 use crate::error::Error;
-use crate::objects::Edge;
+use crate::angle::Angle;
 use crate::objects::Vec4f;
 use crate::objects::Object;
+use crate::json::save_json_to_path;
+use crate::off::save_off_to_path;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+pub const MAGIC_V1: [u8; 4] = [0x4d, 0x4d, 0x4d, 0x4d];
+pub const MAGIC:    [u8; 4] = *b"4DPF";
+pub const VERSION:  u16     = 2;
 
 fn save_with_explorer() -> Option<String> {
-	show_mouse(true);
-	let path = rfd::FileDialog::new()
-		.add_filter("4D object", &["p4d"])
+	show_dialog(|| rfd::FileDialog::new()
+		.add_filter("4D object", &["4dp"])
+		.add_filter("4D object (JSON)", &["json"])
+		.add_filter("4OFF", &["off"])
 		.set_directory("/")
-		.save_file();
-	show_mouse(false);
-	match path {
-		Some(p) => p.as_os_str().to_str().map(|s| s.to_string()),
-		None => None,
-	}
+		.save_file())
 }
 
 fn choose_dir() -> Option<String> {
-	show_mouse(true);
-	let path = rfd::FileDialog::new()
+	show_dialog(|| rfd::FileDialog::new()
 		.set_directory("/")
-		.pick_folder();
-	show_mouse(false);
-	match path {
-		Some(p) => p.as_os_str().to_str().map(|s| s.to_string()),
-		None => None,
+		.pick_folder())
+}
+
+/// File structure (version 2)
+/// First 4 bytes: "4DPF" (magic)
+/// Next 1 byte: byte order of every following field, b'L' (little endian) or b'B' (big endian)
+/// Next 1 byte: reserved, always 0
+/// Next 2 bytes: format version, u16
+/// Next 6 * 4 bytes: view angle, f32 - XY, XZ, XW, YZ, YW, ZW
+/// Next 4 bytes: objects count, u32
+/// Then every object of the scene, one after another:
+///     4 bytes: name length in bytes (n), u32
+///     n bytes: object name, UTF-8 (empty if the object has no name)
+///     4 bytes: vertices count (a), u32
+///     4 bytes: edges count (b), u32
+///     4 bytes: faces count (c), u32
+///     4 bytes: cells count (d), u32
+///     a * 4 * 4 bytes (vertices): f32, f32, f32, f32 - coordinates
+///     b * 2 * 4 bytes (edges): u32, u32 - vertices indices
+///     c faces: u32 - corners count (n), then n * u32 - vertices indices and n * u32 - edges indices
+///     d cells: u32, u32, u32 - vertices (p), edges (q) and faces (r) counts,
+///              then p * u32 - vertices, q * u32 - edges and r * u32 - faces indices
+/// Indices are local to the object they belong to.
+///
+/// Version 1 files have no version field: "MMMM" magic followed by the four counts
+/// and the records of a single object, everything big endian and without name and angle.
+/// They store only triangles and tetrahedra, with fixed size records:
+///     c * 6 * 4 bytes (faces): u32, u32, u32 - vertices indices and u32, u32, u32 - edges indices
+///     d * 14 * 4 bytes (cells): u32 (4 times) - vertices indices, u32 (6 times) - edges, u32 (4 times) - faces
+pub fn write_to<W: Write>(writer: &mut W, objects: &Vec<Object>, angle: &Angle) -> Result<(), Error> {
+	let order = ByteOrder::Little;
+	let mut data = vec![];
+	data.extend_from_slice(&MAGIC);
+	data.push(order.marker());
+	data.push(0);
+	data.append(&mut SData::Ushort(VERSION).serialize(order));
+	data.append(&mut SData::Angle(*angle).serialize(order));
+	data.append(&mut SData::Uint(objects.len() as u32).serialize(order));
+	for obj in objects.iter() {
+		data.append(&mut serialize_object(obj, order));
 	}
+	writer.write_all(&data).map_err(Error::Io)
+}
+
+fn serialize_object(obj: &Object, order: ByteOrder) -> Vec<u8> {
+	let mut data = vec![];
+	data.append(&mut SData::Name(obj.name.clone().unwrap_or_default()).serialize(order));
+	data.append(&mut SData::Uint(obj.vertices.len() as u32).serialize(order));
+	data.append(&mut SData::Uint(obj.edges.len() as u32).serialize(order));
+	data.append(&mut SData::Uint(obj.faces.len() as u32).serialize(order));
+	data.append(&mut SData::Uint(obj.cells.len() as u32).serialize(order));
+	for v in &obj.vertices { data.append(&mut SData::Vector(*v).serialize(order)); }
+	for e in &obj.edges { data.append(&mut SData::Edge(e.a, e.b).serialize(order)); }
+	for f in &obj.faces {
+		data.append(&mut SData::Face(f.vertices.clone(), f.edges.clone()).serialize(order));
+	}
+	for c in &obj.cells {
+		data.append(&mut SData::Cell(c.vertices.clone(), c.edges.clone(), c.faces.clone()).serialize(order));
+	}
+	data
+}
+
+pub fn save_to_path<P: AsRef<Path>>(path: P, objects: &Vec<Object>, angle: &Angle) -> Result<(), Error> {
+	let mut file = File::create(path).map_err(Error::Io)?;
+	write_to(&mut file, objects, angle)
 }
 
-/// File structure
-/// First 8 bytes: 0x4d4d4d4d (extention)
-/// Next 8 bytes: vertices count (a)
-/// Next 8 bytes: edges count (b)
-/// Next 8 bytes: faces count (c)
-/// Next 8 bytes: cells count (d)
-/// Next 8 bytes: XW angle
-/// Next 8 bytes: YW angle
-/// Next 8 bytes: ZW angle
-/// Next a * 4 * 8 bytes (vertices): f32, f32, f32, f32 - coordinates
-/// Next b * 2 * 8 bytes (edges): u32, u32 - vertices indices
-/// Next c * 6 * 8 bytes (faces): u32, u32, u32 - vertices indices and u32, u32, u32 - edges indices
-/// Next d * 14 * 8 bytes (cells): u32 (4 times) - vertices indices, u32 (6 times) - edges, u32 (4 times) - faces
-pub fn save(objects: &Vec<Object>) {
+/// Asks for a file name and saves the scene there, see `write_to`.
+/// Names ending with ".json" are saved as text, see `json::write_json`,
+/// names ending with ".off" as one 4OFF mesh, see `off::write_off`.
+pub fn save(objects: &Vec<Object>, angle: &Angle) {
 	let extention = "4dp";
-	let filename = save_with_explorer().map(|s| format!("{}.{}", s, extention));
-	let path = match filename {
-		Some(dir) => dir,
+	let path = match save_with_explorer() {
+		Some(p) if p.ends_with(".json") || p.ends_with(".off") || p.ends_with(".4dp") => p,
+		Some(p) => format!("{}.{}", p, extention),
 		None => return,
 	};
-	let mut vertices = vec![];
-	let mut edges = vec![];
-	let mut faces = vec![];
-	let mut cells = vec![];
-	for obj in objects.iter() {
-		for v in &obj.vertices {
-			vertices.push(SData::Vector(v.clone()));
-		}
-		for e in &obj.edges {
-			edges.push(SData::Edge(e.a, e.b));
-		}
-		for f in &obj.faces {
-			faces.push(SData::Face(f.0, f.1));
-		}
-		for c in &obj.cells {
-			cells.push(SData::Cell(c.0, c.1, c.2))
-		}
-	}
-	let mut data = vec![];
-	let extention = SData::Uint(0x4d4d4d4d);
-	let vertices_count = SData::Uint(vertices.len() as u32);
-	let edges_count = SData::Uint(edges.len() as u32);
-	let faces_count = SData::Uint(faces.len() as u32);
-	let cells_count = SData::Uint(cells.len() as u32);
-	data.append(&mut extention.serialize());
-	data.append(&mut vertices_count.serialize());
-	data.append(&mut edges_count.serialize());
-	data.append(&mut faces_count.serialize());
-	data.append(&mut cells_count.serialize());
-	for v in vertices { data.append(&mut v.serialize()); }
-	for e in edges { data.append(&mut e.serialize()); }
-	for f in faces { data.append(&mut f.serialize()); }
-	for c in cells { data.append(&mut c.serialize()); }
-	let res = std::fs::write(&path, data);
+	let res = if path.ends_with(".json") {
+		save_json_to_path(&path, objects, angle)
+	} else if path.ends_with(".off") {
+		save_off_to_path(&path, objects)
+	} else {
+		save_to_path(&path, objects, angle)
+	};
 	match res {
 		Ok(_) => println!("{}", path),
 		Err(e) => println!("failed to save, reason: {}", e),
 	}
 }
 
+impl Object {
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+		write_to(writer, &vec![self.clone()], &Angle::new())
+	}
+
+	pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+		save_to_path(path, &vec![self.clone()], &Angle::new())
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ByteOrder {
+	Little,
+	Big,
+}
+
+impl ByteOrder {
+	pub fn marker(self) -> u8 {
+		match self {
+			ByteOrder::Little => b'L',
+			ByteOrder::Big    => b'B',
+		}
+	}
+
+	pub fn from_marker(marker: u8) -> Option<Self> {
+		match marker {
+			b'L' => Some(ByteOrder::Little),
+			b'B' => Some(ByteOrder::Big),
+			_ => None,
+		}
+	}
+}
+
 pub enum SData {
 	Vector(Vec4f),
 	Edge(usize, usize),
 	Face(
-		(usize, usize, usize),
-		(usize, usize, usize)
+		Vec<usize>, // vertices
+		Vec<usize>, // edges
 	),
 	Cell(
-		(usize, usize, usize, usize),
-		(usize, usize, usize, usize, usize, usize),
-		(usize, usize, usize, usize)
+		Vec<usize>, // vertices
+		Vec<usize>, // edges
+		Vec<usize>, // faces
 	),
+	Angle(Angle),
+	Name(String),
+	Ushort(u16),
 	Uint(u32),
-	Ulong(u64),
 	Float(f32),
 }
 
 impl SData {
-	pub fn as_vector(&self) -> Result<Vec4f, Error> {
-		if let Self::Vector(v) = self { Ok(v.clone()) }
-		else { Err(Error::Unknown) }
-	}
-
-	pub fn as_edge(&self) -> Result<Edge, Error> {
-		if let Self::Edge(a, b) = self { Ok(Edge::new(*a, *b)) }
-		else { Err(Error::Unknown) }
-	}
-
-	pub fn as_face(&self) -> Result<(
-		(usize, usize, usize),
-		(usize, usize, usize),
-		bool
-	), Error> {
-		if let Self::Face((v0, v1, v2), (a, b, c)) = self {
-			Ok(((*v0, *v1, *v2), (*a, *b, *c), false))
-		} else { Err(Error::Unknown) }
-	}
-
-	pub fn as_cell(&self) -> Result<(
-		(usize, usize, usize, usize),
-		(usize, usize, usize, usize, usize, usize),
-		(usize, usize, usize, usize),
-		bool,
-	), Error> {
-		if let Self::Cell(vertices, edges, faces) = *self {
-			Ok((vertices, edges, faces, false))
-		} else { Err(Error::Unknown) }
-	}
-
-	fn serialize(&self) -> Vec<u8> {
+	pub fn serialize(&self, order: ByteOrder) -> Vec<u8> {
 		let mut res = vec![];
 		match self {
 			SData::Vector(v) => {
 				let coords = v.as_vec();
 				for bytes in coords {
-					res.append(&mut SData::Float(bytes).serialize());
+					res.append(&mut SData::Float(bytes).serialize(order));
 				}
 			},
 			SData::Edge(a, b) => {
-				res.append(&mut SData::Uint(*a as u32).serialize());
-				res.append(&mut SData::Uint(*b as u32).serialize());
+				res.append(&mut SData::Uint(*a as u32).serialize(order));
+				res.append(&mut SData::Uint(*b as u32).serialize(order));
 			},
 			SData::Face(v, e) => {
-				for uint in vec![v.0, v.1, v.2, e.0, e.1, e.2] {
-					res.append(&mut SData::Uint(uint as u32).serialize());
+				res.append(&mut SData::Uint(v.len() as u32).serialize(order));
+				for uint in v.iter().chain(e.iter()) {
+					res.append(&mut SData::Uint(*uint as u32).serialize(order));
 				}
 			},
 			SData::Cell(v, e, f) => {
-				for uint in vec![
-					v.0, v.1, v.2, v.3,
-					e.0, e.1, e.2, e.3, e.4, e.5,
-					f.0, f.1, f.2, f.3,
-				] {
-					res.append(&mut SData::Uint(uint as u32).serialize());
+				for count in vec![v.len(), e.len(), f.len()] {
+					res.append(&mut SData::Uint(count as u32).serialize(order));
+				}
+				for uint in v.iter().chain(e.iter()).chain(f.iter()) {
+					res.append(&mut SData::Uint(*uint as u32).serialize(order));
+				}
+			},
+			SData::Angle(a) => {
+				for float in vec![a.xy, a.xz, a.xw, a.yz, a.yw, a.zw] {
+					res.append(&mut SData::Float(float).serialize(order));
 				}
 			},
+			SData::Name(name) => {
+				res.append(&mut SData::Uint(name.len() as u32).serialize(order));
+				res.extend_from_slice(name.as_bytes());
+			},
+			SData::Ushort(u) => {
+				res = match order {
+					ByteOrder::Little => (*u).to_le_bytes().into(),
+					ByteOrder::Big    => (*u).to_be_bytes().into(),
+				};
+			},
 			SData::Uint(u) => {
-				res = (*u).to_be_bytes().into();
+				res = match order {
+					ByteOrder::Little => (*u).to_le_bytes().into(),
+					ByteOrder::Big    => (*u).to_be_bytes().into(),
+				};
 			},
-			SData::Ulong(u) => {
-				res = (*u).to_be_bytes().into();
-			}
 			SData::Float(f) => {
-				res = (*f).to_be_bytes().into();
+				res = match order {
+					ByteOrder::Little => (*f).to_le_bytes().into(),
+					ByteOrder::Big    => (*f).to_be_bytes().into(),
+				};
 			},
 		}
 		return res
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,69 @@
+use crate::window::Window;
+use crate::objects::Vec4f;
+use crate::objects::Object;
+use crate::objects::UNIT_AXES;
+const SLIDER_MARGIN: f32 = 40.0; // from the bottom of the window
+
+/// Viewport mode showing the cross-sections of the objects with a hyperplane
+/// orthogonal to one of the axes, the offset is swept with a slider.
+#[derive(Debug, Copy, Clone)]
+pub struct SliceView {
+    pub enabled:  bool,
+    pub axis:     usize, // index of the hyperplane normal in `AXIS_NAMES`
+    pub position: f32,   // slider position from 0 (lowest vertex) to 1 (highest)
+    pub grabbed:  bool,
+}
+
+impl SliceView {
+    pub fn new() -> Self {
+        SliceView {
+            enabled:  false,
+            axis:     3,
+            position: 0.5,
+            grabbed:  false,
+        }
+    }
+
+    pub fn normal(&self) -> Vec4f {
+        UNIT_AXES[self.axis]
+    }
+
+    /// Smallest and largest offsets at which the hyperplane meets the objects.
+    pub fn range(&self, objects: &Vec<Object>) -> (f32, f32) {
+        let normal = self.normal();
+        let values = objects.iter().flat_map(|o| o.vertices.iter().map(|v| v.dot(normal)));
+        let (min, max) = values.fold((f32::MAX, f32::MIN), |(min, max), d| (min.min(d), max.max(d)));
+        if min > max { (-1.0, 1.0) } else { (min, max) }
+    }
+
+    pub fn offset(&self, objects: &Vec<Object>) -> f32 {
+        let (min, max) = self.range(objects);
+        min + (max - min) * self.position
+    }
+
+    pub fn slices(&self, objects: &Vec<Object>) -> Vec<Object> {
+        let offset = self.offset(objects);
+        objects.iter().map(|o| o.slice(self.normal(), offset)).collect()
+    }
+
+    /// Slider track as (x, y, width), centered under the viewport.
+    pub fn slider(&self, window: &Window) -> (f32, f32, f32) {
+        let (x, y, w, h) = window.as_tuple();
+        (x + w / 4.0, y + h - SLIDER_MARGIN, w / 2.0)
+    }
+
+    pub fn slider_hovered(&self, xy: (f32, f32), window: &Window) -> bool {
+        let (x, y, w) = self.slider(window);
+        xy.0 >= x - 10.0 && xy.0 <= x + w + 10.0 && (xy.1 - y).abs() <= 10.0
+    }
+
+    pub fn drag_to(&mut self, xy: (f32, f32), window: &Window) {
+        let (x, _, w) = self.slider(window);
+        self.position = ((xy.0 - x) / w).max(0.0).min(1.0);
+    }
+
+    pub fn next_axis(&mut self) {
+        self.axis = (self.axis + 1) % 4;
+        self.position = 0.5;
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use crate::draw::*;
+use crate::error::Error;
+use crate::objects::Axes;
+use crate::objects::Object;
+use macroquad::prelude::Color;
+use crate::dialog::show_dialog;
+
+fn save_with_explorer() -> Option<String> {
+    show_dialog(|| rfd::FileDialog::new()
+        .add_filter("SVG image", &["svg"])
+        .set_directory("/")
+        .save_file())
+}
+
+fn rgb(color: Color) -> String {
+    format!(
+        "rgb({}, {}, {})",
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8,
+    )
+}
+
+fn line(a: (f32, f32), b: (f32, f32), width: f32, color: Color) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\"/>\n",
+        a.0, a.1, b.0, b.1, rgb(color), color.a, width,
+    )
+}
+
+fn circle(c: (f32, f32), r: f32, color: Color) -> String {
+    format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>\n",
+        c.0, c.1, r, rgb(color), color.a,
+    )
+}
+
+/// Writes the current viewport as SVG. Uses projections computed by
+/// `Object::calc_vertices` and `Axes::calc`, so call it after them.
+pub fn write_svg<W: Write>(
+    writer:        &mut W,
+    objects:       &Vec<Object>,
+    axes:          &Axes,
+    size:          (f32, f32),
+    show_vertices: bool,
+) -> Result<(), Error> {
+    let (w, h) = size;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        w, h, w, h,
+    );
+    svg += &format!("<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", w, h, rgb(BACKGROUND_COLOR));
+    for obj in objects {
+        for e in &obj.edges {
+            if let (Some(a), Some(b)) = (obj.vertices[e.a].get_proj(), obj.vertices[e.b].get_proj()) {
+                if e.selected {
+                    svg += &line(a, b, 2.0, SELECTED_EDGE_OUTLINE);
+                    svg += &line(a, b, 1.0, SELECTED_EDGE_COLOR);
+                } else {
+                    svg += &line(a, b, 1.0, EDGE_COLOR);
+                }
+            }
+        }
+        if !show_vertices { continue; }
+        for v in &obj.vertices {
+            if let Some(proj) = v.get_proj() {
+                if v.selected {
+                    svg += &circle(proj, 3.0, SELECTED_VERTEX_OUTLINE);
+                    svg += &circle(proj, 2.0, SELECTED_VERTEX_COLOR);
+                } else {
+                    svg += &circle(proj, 2.0, VERTEX_COLOR);
+                }
+            }
+        }
+    }
+    let offset = (axes.offset.0, axes.offset.1 + h);
+    for (axe, name) in vec![(axes.x, "X"), (axes.y, "Y"), (axes.z, "Z"), (axes.w, "W")] {
+        if let Some(xy) = axe.centered(w, h) {
+            let end = (xy.0 + offset.0, xy.1 + offset.1);
+            svg += &line(offset, end, 2.0, axe_color(name));
+            svg += &format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"18\" fill=\"{}\">{}</text>\n",
+                end.0 + 10.0, end.1, rgb(AXE_LABEL_COLOR), name,
+            );
+        }
+    }
+    svg += "</svg>\n";
+    writer.write_all(svg.as_bytes()).map_err(Error::Io)
+}
+
+pub fn save_svg_to_path<P: AsRef<Path>>(
+    path:          P,
+    objects:       &Vec<Object>,
+    axes:          &Axes,
+    size:          (f32, f32),
+    show_vertices: bool,
+) -> Result<(), Error> {
+    let mut file = File::create(path).map_err(Error::Io)?;
+    write_svg(&mut file, objects, axes, size, show_vertices)
+}
+
+/// Asks for a file name and exports the viewport there, see `write_svg`.
+pub fn export_svg(objects: &Vec<Object>, axes: &Axes, size: (f32, f32), show_vertices: bool) {
+    let path = match save_with_explorer() {
+        Some(p) if p.ends_with(".svg") => p,
+        Some(p) => format!("{}.svg", p),
+        None => return,
+    };
+    match save_svg_to_path(&path, objects, axes, size, show_vertices) {
+        Ok(_) => println!("{}", path),
+        Err(e) => println!("failed to export, reason: {}", e),
+    }
+}
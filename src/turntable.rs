@@ -0,0 +1,189 @@
+use crate::angle::Plane;
+use crate::rotor::Rotor;
+use crate::button::Button;
+use crate::button::ButtonType;
+
+const MENU_X:        f32 = 160.0; // the turntable menu button in the top left row
+const DEFAULT_SPEED: f32 = 0.5;   // radians per second
+const SPEED_STEP:    f32 = 0.25;
+
+/// Items of the turntable menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TurntableItem {
+    Simple,
+    LeftIsoclinic,
+    RightIsoclinic,
+    FirstPlane,
+    SecondPlane,
+    FirstSpeed,
+    SecondSpeed,
+    Pause,
+    Stop,
+}
+
+impl TurntableItem {
+    pub const ALL: [TurntableItem; 9] = [
+        TurntableItem::Simple,
+        TurntableItem::LeftIsoclinic,
+        TurntableItem::RightIsoclinic,
+        TurntableItem::FirstPlane,
+        TurntableItem::SecondPlane,
+        TurntableItem::FirstSpeed,
+        TurntableItem::SecondSpeed,
+        TurntableItem::Pause,
+        TurntableItem::Stop,
+    ];
+}
+
+/// Continuous rotation of the view in one plane, or in two planes at once.
+/// Every frame turns the current orientation, so the view can still be
+/// dragged, and stopping leaves the last orientation in it.
+#[derive(Debug, Copy, Clone)]
+pub struct Turntable {
+    pub enabled: bool,
+    pub paused:  bool,
+    pub first:   Plane,
+    pub second:  Option<Plane>,
+    pub speeds:  (f32, f32), // radians per second in `first` and `second`
+}
+
+impl Turntable {
+    pub fn new() -> Self {
+        Turntable {
+            enabled: false,
+            paused:  false,
+            first:   Plane::XW,
+            second:  None,
+            speeds:  (DEFAULT_SPEED, DEFAULT_SPEED),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.enabled && !self.paused
+    }
+
+    /// Starts one of the preset rotations: XW alone, or XY and ZW at once.
+    /// Equal speeds in XY and ZW turn the right quaternion of `Rotor` only,
+    /// opposite ones the left.
+    pub fn preset(&mut self, item: TurntableItem) {
+        let speed = self.speeds.0.abs().max(SPEED_STEP);
+        match item {
+            TurntableItem::Simple => {
+                self.first = Plane::XW;
+                self.second = None;
+                self.speeds = (speed, speed);
+            },
+            TurntableItem::LeftIsoclinic => {
+                self.first = Plane::XY;
+                self.second = Some(Plane::ZW);
+                self.speeds = (speed, -speed);
+            },
+            TurntableItem::RightIsoclinic => {
+                self.first = Plane::XY;
+                self.second = Some(Plane::ZW);
+                self.speeds = (speed, speed);
+            },
+            _ => return,
+        }
+        self.enabled = true;
+        self.paused = false;
+    }
+
+    /// Handles a click on a menu item, `back` (SHIFT) cycles planes
+    /// backwards and lowers speeds.
+    pub fn click(&mut self, item: TurntableItem, back: bool) {
+        let step = if back { -SPEED_STEP } else { SPEED_STEP };
+        let shift = if back { Plane::ALL.len() - 1 } else { 1 };
+        let index = |p: Plane| Plane::ALL.iter().position(|q| *q == p).unwrap();
+        match item {
+            TurntableItem::FirstPlane => self.first = Plane::ALL[(index(self.first) + shift) % Plane::ALL.len()],
+            TurntableItem::SecondPlane => {
+                // `None` is one more step of the cycle
+                let i = self.second.map(|p| index(p) + 1).unwrap_or(0);
+                let i = (i + if back { Plane::ALL.len() } else { 1 }) % (Plane::ALL.len() + 1);
+                self.second = if i == 0 { None } else { Some(Plane::ALL[i - 1]) };
+            },
+            TurntableItem::FirstSpeed => self.speeds.0 += step,
+            TurntableItem::SecondSpeed => self.speeds.1 += step,
+            TurntableItem::Pause => {
+                if self.enabled { self.paused = !self.paused; }
+                else { self.enabled = true; }
+            },
+            TurntableItem::Stop => self.enabled = false,
+            preset => self.preset(preset),
+        }
+    }
+
+    pub fn label(&self, item: TurntableItem) -> String {
+        match item {
+            TurntableItem::Simple => "Простое вращение".to_string(),
+            TurntableItem::LeftIsoclinic => "Левое изоклинное".to_string(),
+            TurntableItem::RightIsoclinic => "Правое изоклинное".to_string(),
+            TurntableItem::FirstPlane => format!("Плоскость 1: {}", self.first.name()),
+            TurntableItem::SecondPlane => format!("Плоскость 2: {}", self.second.map(|p| p.name()).unwrap_or("нет")),
+            TurntableItem::FirstSpeed => format!("Скорость 1: {:.2} рад/с", self.speeds.0),
+            TurntableItem::SecondSpeed => format!("Скорость 2: {:.2} рад/с", self.speeds.1),
+            TurntableItem::Pause if self.is_running() => "Пауза".to_string(),
+            TurntableItem::Pause => "Продолжить".to_string(),
+            TurntableItem::Stop => "Остановить".to_string(),
+        }
+    }
+
+    /// Rotation over `dt` seconds.
+    pub fn rotor(&self, dt: f32) -> Rotor {
+        let first = Rotor::from_plane(self.first, self.speeds.0 * dt);
+        match self.second {
+            Some(second) => Rotor::from_plane(second, self.speeds.1 * dt) * first,
+            None => first,
+        }
+    }
+
+    /// Turns `rotor` by the rotation over `dt` seconds while running.
+    pub fn advance(&self, dt: f32, rotor: &mut Rotor) {
+        if !self.is_running() { return; }
+        *rotor = (self.rotor(dt) * *rotor).norm();
+    }
+}
+
+/// Opens the turntable menu under its button in the top left row, or closes it.
+pub fn toggle_turntable_menu(buttons: &mut Vec<Button>) {
+    Button::toggle_menu(buttons, MENU_X, TurntableItem::ALL.iter().map(|i| ButtonType::Turntable(*i)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotor::Quat;
+
+    /// `q` is 1 or -1, the sign of a quaternion pair is free.
+    fn is_identity(q: Quat) -> bool {
+        (q.w.abs() - 1.0).abs() < 1e-5 && q.x.abs() < 1e-5 && q.y.abs() < 1e-5 && q.z.abs() < 1e-5
+    }
+
+    #[test]
+    fn isoclinic() {
+        let mut turntable = Turntable::new();
+        turntable.preset(TurntableItem::LeftIsoclinic);
+        let r = turntable.rotor(0.7);
+        assert!(!is_identity(r.left) && is_identity(r.right));
+        turntable.preset(TurntableItem::RightIsoclinic);
+        let r = turntable.rotor(0.7);
+        assert!(is_identity(r.left) && !is_identity(r.right));
+        turntable.preset(TurntableItem::Simple);
+        let r = turntable.rotor(0.7);
+        assert!(!is_identity(r.left) && !is_identity(r.right));
+    }
+
+    #[test]
+    fn second_plane() {
+        let mut turntable = Turntable::new();
+        for _ in 0..Plane::ALL.len() {
+            turntable.click(TurntableItem::SecondPlane, false);
+            assert!(turntable.second.is_some());
+        }
+        turntable.click(TurntableItem::SecondPlane, false);
+        assert_eq!(turntable.second, None);
+        turntable.click(TurntableItem::SecondPlane, true);
+        assert_eq!(turntable.second, Some(Plane::ZW));
+    }
+}
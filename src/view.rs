@@ -0,0 +1,114 @@
+use crate::rotor::Rotor;
+use crate::button::Button;
+use crate::button::ButtonType;
+use crate::objects::Camera;
+use crate::objects::Object;
+use crate::objects::Element;
+use crate::objects::Projection;
+
+const MENU_X:          f32 = 140.0; // the view menu button in the top left row
+const TRANSITION_TIME: f32 = 0.4;   // seconds to turn the camera to a preset
+
+/// Symmetric views of the objects, applied to the camera orientation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ViewPreset {
+    VertexFirst,
+    EdgeFirst,
+    FaceFirst,
+    CellFirst,
+    CoxeterPlane,
+}
+
+impl ViewPreset {
+    pub const ALL: [ViewPreset; 5] = [
+        ViewPreset::VertexFirst,
+        ViewPreset::EdgeFirst,
+        ViewPreset::FaceFirst,
+        ViewPreset::CellFirst,
+        ViewPreset::CoxeterPlane,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewPreset::VertexFirst  => "Вершиной вперёд",
+            ViewPreset::EdgeFirst    => "Ребром вперёд",
+            ViewPreset::FaceFirst    => "Гранью вперёд",
+            ViewPreset::CellFirst    => "Ячейкой вперёд",
+            ViewPreset::CoxeterPlane => "Плоскость Коксетера",
+        }
+    }
+
+    /// Turns the camera to the preset. Element-first views look at the selected
+    /// elements of that kind, or at the one nearest to the camera. The Coxeter
+    /// plane view shows the Petrie polygon of the first object orthogonally.
+    /// Returns false if the view does not exist for the objects.
+    pub fn apply(self, objects: &Vec<Object>, rotor: &Rotor, camera: &mut Camera) -> bool {
+        let element = match self {
+            ViewPreset::VertexFirst  => Element::Vertex,
+            ViewPreset::EdgeFirst    => Element::Edge,
+            ViewPreset::FaceFirst    => Element::Face,
+            ViewPreset::CellFirst    => Element::Cell,
+            ViewPreset::CoxeterPlane => {
+                let plane = objects.iter().find(|o| !o.edges.is_empty()).and_then(|o| o.coxeter_plane());
+                return match plane {
+                    Some((u, w)) => {
+                        camera.orient(rotor.apply(u), rotor.apply(w));
+                        camera.projection = Projection::Orthographic;
+                        camera.projection3d = Projection::Orthographic;
+                        true
+                    },
+                    None => false,
+                };
+            },
+        };
+        // toward the camera in the coordinates of the objects
+        let toward = rotor.inverse().apply(camera.n.norm() * -1.0);
+        let selected = objects.iter().position(|o| o.vertices.iter().any(|v| v.selected)).unwrap_or(0);
+        match objects.get(selected).and_then(|o| o.element_direction(element, toward)) {
+            Some(dir) => {
+                camera.face(rotor.apply(dir));
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/// Smooth turn of the camera to a view preset: the orientation follows
+/// `Rotor::slerp` from the old basis to the new one, the pan fades out.
+#[derive(Debug, Copy, Clone)]
+pub struct ViewTransition {
+    from: Camera,
+    to:   Camera,
+    turn: Rotor, // takes the basis of `from` to the one of `to`
+    time: f32,
+}
+
+impl ViewTransition {
+    pub fn new(from: Camera, to: Camera) -> Self {
+        ViewTransition {
+            from,
+            to,
+            turn: Rotor::from_bases(&from.basis(), &to.basis()),
+            time: 0.0,
+        }
+    }
+
+    /// Moves the camera `dt` seconds further, returns false once it has arrived.
+    pub fn advance(&mut self, dt: f32, camera: &mut Camera) -> bool {
+        self.time = (self.time + dt).min(TRANSITION_TIME);
+        let t = self.time / TRANSITION_TIME;
+        let turn = Rotor::identity().slerp(&self.turn, t);
+        let pan = self.to.c - self.turn.apply(self.from.c);
+        camera.c = turn.apply(self.from.c) + pan * t;
+        camera.n = turn.apply(self.from.n);
+        camera.up = turn.apply(self.from.up);
+        camera.over = turn.apply(self.from.over);
+        self.time < TRANSITION_TIME
+    }
+}
+
+/// Opens the view menu under its button in the top left row, or closes it.
+pub fn toggle_view_menu(buttons: &mut Vec<Button>) {
+    Button::toggle_menu(buttons, MENU_X, ViewPreset::ALL.iter().map(|p| ButtonType::View(*p)).collect());
+}
@@ -416,15 +416,30 @@ impl OverlappingWindow {
         let w = screen_width();
         let hotkeys = vec![
             ("Выделить", "ЛКМ"),
-            ("Вращать (XW, YW, ZW)", "ПКМ + <>, ПКМ + КОЛЕСО"),
-            ("Вращать (XZ, YZ)", "LSHIFT + ПКМ + <>"),
+            ("Вращать в 4D (глубина W)", "ПКМ + <>"),
+            ("Вращать в 3D (глубина Z)", "LSHIFT + ПКМ + <>"),
+            ("Вращать ZW", "ПКМ + КОЛЕСО"),
+            ("Приблизить / отдалить", "КОЛЕСО"),
+            ("Сдвинуть камеру", "СКМ + <>"),
+            ("Сменить проекцию 4D → 3D", "O"),
+            ("Сменить проекцию 3D → 2D", "SHIFT + O"),
+            ("Вершиной / ребром / гранью / ячейкой вперёд", "1 / 2 / 3 / 4"),
+            ("Плоскость Коксетера", "5"),
+            ("Вращение в 4D (вкл / выкл)", "R"),
+            ("Пауза / продолжить вращение", "ПРОБЕЛ"),
             ("Экструдировать", "E"),
             ("Соединить вершины", "F"),
             ("Заполнить 2D поверхность", "SHIFT + F"),
             ("Заполнить 3D поверхность", "CTRL + F"),
+            ("Выпуклая оболочка", "H"),
+            ("Сечение гиперплоскостью", "S"),
+            ("Сменить ось сечения", "SHIFT + S"),
             ("Копировать", "CTRL + C"),
             ("Вставить", "CTRL + V"),
             ("Дублировать", "CTRL + D"),
+            ("Декартово произведение", "CTRL + P"),
+            ("Тегум-произведение", "CTRL + T"),
+            ("Соединение / пирамида", "CTRL + J"),
             ("Удалить", "DEL"),
         ];
         content.push(ContentItem::header(
@@ -481,6 +496,9 @@ impl OverlappingWindow {
     }
 }
 
+/// Distance between the centers of the "Создать" tiles.
+const TILE_STEP: f32 = 150.0;
+
 #[derive(Debug, Clone)]
 pub struct StartWindow {
     pub config: Parameters,
@@ -492,24 +510,27 @@ pub struct StartWindow {
 impl StartWindow {
     pub fn new(w: f32, h: f32) -> Result<Self, String> {
         let mut buttons = vec![];
-        buttons.push(Button::Click(ClickButton::new(
-            -160.0,
-            -80.0,
-            100.0,
-            100.0,
-            Some("sprites/tesseract.png"),
-            Align::Middle,
-            ButtonType::CreateTesseract,
-        )));
-        buttons.push(Button::Click(ClickButton::new(
-            60.0,
-            -80.0,
-            100.0,
-            100.0,
-            Some("sprites/sphere3d.png"),
-            Align::Middle,
-            ButtonType::CreateSphere3D,
-        )));
+        let tiles = vec![
+            ("sprites/cell5.png",     "5-ячейник",   ButtonType::CreateCell5),
+            ("sprites/tesseract.png", "Тессеракт",   ButtonType::CreateTesseract),
+            ("sprites/cell16.png",    "16-ячейник",  ButtonType::CreateCell16),
+            ("sprites/cell24.png",    "24-ячейник",  ButtonType::CreateCell24),
+            ("sprites/cell120.png",   "120-ячейник", ButtonType::CreateCell120),
+            ("sprites/cell600.png",   "600-ячейник", ButtonType::CreateCell600),
+            ("sprites/sphere3d.png",  "3D сфера",    ButtonType::CreateSphere3D),
+        ];
+        let first = -(tiles.len() as f32 * TILE_STEP) / 2.0;
+        for (i, (sprite, _, btype)) in tiles.iter().enumerate() {
+            buttons.push(Button::Click(ClickButton::new(
+                first + TILE_STEP * i as f32 + (TILE_STEP - 100.0) / 2.0,
+                -80.0,
+                100.0,
+                100.0,
+                Some(*sprite),
+                Align::Middle,
+                btype.clone(),
+            )));
+        }
         buttons.push(Button::Click(ClickButton::new(
             -15.0,
             0.0,
@@ -526,7 +547,7 @@ impl StartWindow {
             70.0,
             Some("sprites/import_obj.png"),
             Align::Middle,
-            ButtonType::CreateSphere3D,
+            ButtonType::ImportObj,
         )));
         let mut content = Content::new();
         content.push(ContentItem::header(
@@ -543,18 +564,14 @@ impl StartWindow {
             Color::new(0.4, 0.4, 0.4, 1.0),
             Align::TopCenter,
         )?);
-        content.push(ContentItem::text(
-            "Тессеракт",
-            (-110.0, h * 0.5 + 5.0),
-            Color::new(0.4, 0.4, 0.4, 1.0),
-            Align::TopCenter,
-        ));
-        content.push(ContentItem::text(
-            "3D сфера",
-            (110.0, h * 0.5 + 5.0),
-            Color::new(0.4, 0.4, 0.4, 1.0),
-            Align::TopCenter,
-        ));
+        for (i, (_, label, _)) in tiles.iter().enumerate() {
+            content.push(ContentItem::text(
+                label,
+                (first + TILE_STEP * (i as f32 + 0.5), h * 0.5 + 5.0),
+                Color::new(0.4, 0.4, 0.4, 1.0),
+                Align::TopCenter,
+            ));
+        }
         content.push(ContentItem::text(
             ".OBJ",
             (0.0, -h * 0.1 - 45.0),